@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lloyd_massiah_when_i_work_take_home::employee_shift::{
+    config::SummaryConfig, employee::EmployeeShift, summarize_shifts::summarize_shifts,
+};
+
+const SHIFTS_PER_EMPLOYEE: u64 = 20;
+
+/// Generates `count` shifts spread over employees working one 8 hour shift a day, with every tenth
+/// shift overlapping the one before it so overlapping shifts are found too
+fn generate_shifts(count: u64) -> Vec<EmployeeShift> {
+    let first_shift_start: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-07-01T13:00:00Z")
+        .unwrap()
+        .into();
+
+    (0..count)
+        .map(|shift_id| {
+            let employee_id = shift_id / SHIFTS_PER_EMPLOYEE;
+            let day = (shift_id % SHIFTS_PER_EMPLOYEE) as i64;
+            let mut start_time = first_shift_start + Duration::days(day);
+            if day % 10 == 9 {
+                start_time -= Duration::hours(20);
+            }
+
+            EmployeeShift {
+                file: Arc::from("generated"),
+                record: shift_id as usize + 1,
+                shift_id,
+                employee_id,
+                start_time,
+                end_time: start_time + Duration::hours(8),
+                time_zone: None,
+                breaks: vec![],
+            }
+        })
+        .collect()
+}
+
+fn bench_summarize_shifts(c: &mut Criterion) {
+    let config = SummaryConfig::default();
+    let mut group = c.benchmark_group("summarize_shifts");
+    group.sample_size(10);
+
+    // time per shift should stay roughly flat as the number of shifts grows by 10x at a time,
+    // apart from the log factor from sorting
+    for count in [10_000, 100_000, 1_000_000] {
+        group.throughput(Throughput::Elements(count));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter_batched(
+                || generate_shifts(count),
+                |shifts| summarize_shifts(shifts, &config),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_summarize_shifts);
+criterion_main!(benches);
@@ -0,0 +1,292 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::employee_shift::{
+    config::{AutomaticBreak, BreakRules},
+    employee::{Break, BreakViolationKind, EmployeeShift},
+    hours::Hours,
+};
+
+/// Unpaid breaks taken during a shift that runs from `start_time` to `end_time`. Breaks recorded
+/// on the shift are used when there are any, otherwise shifts long enough for the automatic break
+/// get one centered on the middle of the shift.
+pub fn unpaid_breaks(
+    shift: &EmployeeShift,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    automatic_break: Option<AutomaticBreak>,
+) -> Vec<Break> {
+    if !shift.breaks.is_empty() {
+        return shift.breaks.clone();
+    }
+
+    match automatic_break {
+        Some(automatic_break)
+            if Hours::from_duration(end_time - start_time)
+                > Hours::from_hours(automatic_break.after_hours) =>
+        {
+            let length = TimeDelta::minutes(automatic_break.minutes as i64);
+            let middle = start_time + (end_time - start_time) / 2;
+            let break_start = (middle - length / 2).max(start_time);
+            vec![Break {
+                start_time: break_start,
+                end_time: (break_start + length).min(end_time),
+            }]
+        }
+        _ => vec![],
+    }
+}
+
+/// Splits a shift into the periods worked between its breaks. Breaks are clipped to the shift,
+/// since rounding can move the shift's start or end past a break, and overlapping breaks are only
+/// deducted once.
+pub fn paid_periods(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    breaks: &[Break],
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut breaks = breaks.to_vec();
+    breaks.sort_by_key(|shift_break| shift_break.start_time);
+
+    let mut periods = Vec::with_capacity(breaks.len() + 1);
+    let mut period_start = start_time;
+    for shift_break in breaks {
+        let break_start = shift_break.start_time.clamp(period_start, end_time);
+        if break_start > period_start {
+            periods.push((period_start, break_start));
+        }
+        period_start = period_start.max(shift_break.end_time.min(end_time));
+    }
+    if period_start < end_time {
+        periods.push((period_start, end_time));
+    }
+
+    periods
+}
+
+/// Checks the meal breaks taken between a shift's paid periods against the break rules. A meal
+/// break is a gap between paid periods at least as long as the minimum meal break.
+pub fn break_violations(
+    paid_periods: &[(DateTime<Utc>, DateTime<Utc>)],
+    rules: &BreakRules,
+) -> Vec<BreakViolationKind> {
+    let minimum_meal_break = TimeDelta::minutes(rules.minimum_meal_break_minutes as i64);
+
+    // hours worked before each meal break started
+    let mut meal_breaks = vec![];
+    let mut hours_worked = Hours::ZERO;
+    for (index, (period_start, period_end)) in paid_periods.iter().enumerate() {
+        hours_worked += Hours::from_duration(*period_end - *period_start);
+        if let Some((next_period_start, _)) = paid_periods.get(index + 1) {
+            if *next_period_start - *period_end >= minimum_meal_break {
+                meal_breaks.push(hours_worked);
+            }
+        }
+    }
+
+    let mut violations = vec![];
+    let mut check_meal_break = |meal_break: usize,
+                                threshold: f64,
+                                missing: BreakViolationKind,
+                                late: BreakViolationKind| {
+        let threshold = Hours::from_hours(threshold);
+        if hours_worked <= threshold {
+            return;
+        }
+        match meal_breaks.get(meal_break) {
+            None => violations.push(missing),
+            Some(hours_worked_before) if *hours_worked_before > threshold => violations.push(late),
+            Some(_) => {}
+        }
+    };
+
+    check_meal_break(
+        0,
+        rules.first_meal_break_hours,
+        BreakViolationKind::MissingMealBreak,
+        BreakViolationKind::LateMealBreak,
+    );
+    if let Some(second_meal_break_hours) = rules.second_meal_break_hours {
+        check_meal_break(
+            1,
+            second_meal_break_hours,
+            BreakViolationKind::MissingSecondMealBreak,
+            BreakViolationKind::LateSecondMealBreak,
+        );
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
+    fn shift_break(start_time: &str, end_time: &str) -> Break {
+        Break {
+            start_time: utc(start_time),
+            end_time: utc(end_time),
+        }
+    }
+
+    #[test]
+    fn test_paid_periods_between_breaks() {
+        let periods = paid_periods(
+            utc("2024-07-01 09:00"),
+            utc("2024-07-01 17:00"),
+            &[
+                shift_break("2024-07-01 15:00", "2024-07-01 15:15"),
+                shift_break("2024-07-01 12:00", "2024-07-01 12:30"),
+                // overlaps the break before it
+                shift_break("2024-07-01 12:15", "2024-07-01 12:45"),
+            ],
+        );
+
+        assert_eq!(
+            periods,
+            [
+                (utc("2024-07-01 09:00"), utc("2024-07-01 12:00")),
+                (utc("2024-07-01 12:45"), utc("2024-07-01 15:00")),
+                (utc("2024-07-01 15:15"), utc("2024-07-01 17:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_breaks_are_clipped_to_the_shift() {
+        // the shift was rounded to start after the break started
+        let periods = paid_periods(
+            utc("2024-07-01 09:00"),
+            utc("2024-07-01 17:00"),
+            &[
+                shift_break("2024-07-01 08:55", "2024-07-01 09:10"),
+                shift_break("2024-07-01 16:50", "2024-07-01 17:05"),
+            ],
+        );
+
+        assert_eq!(
+            periods,
+            [(utc("2024-07-01 09:10"), utc("2024-07-01 16:50"))]
+        );
+    }
+
+    #[test]
+    fn test_automatic_break() {
+        let shift =
+            |end_time: &str| EmployeeShift::test_shift(1, utc("2024-07-01 09:00"), utc(end_time));
+        let automatic_break = Some(AutomaticBreak::default());
+
+        // 6 hours exactly isn't over 6 hours
+        let six_hours = shift("2024-07-01 15:00");
+        assert_eq!(
+            unpaid_breaks(
+                &six_hours,
+                six_hours.start_time,
+                six_hours.end_time,
+                automatic_break
+            ),
+            []
+        );
+
+        let eight_hours = shift("2024-07-01 17:00");
+        assert_eq!(
+            unpaid_breaks(
+                &eight_hours,
+                eight_hours.start_time,
+                eight_hours.end_time,
+                automatic_break
+            ),
+            [shift_break("2024-07-01 12:45", "2024-07-01 13:15")]
+        );
+
+        // recorded breaks replace the automatic break
+        let with_break = EmployeeShift {
+            breaks: vec![shift_break("2024-07-01 11:00", "2024-07-01 11:20")],
+            ..shift("2024-07-01 17:00")
+        };
+        assert_eq!(
+            unpaid_breaks(
+                &with_break,
+                with_break.start_time,
+                with_break.end_time,
+                automatic_break
+            ),
+            [shift_break("2024-07-01 11:00", "2024-07-01 11:20")]
+        );
+    }
+
+    #[test]
+    fn test_break_violations() {
+        let rules = BreakRules::default();
+        let violations = |end_time: &str, breaks: &[Break]| {
+            break_violations(
+                &paid_periods(utc("2024-07-01 08:00"), utc(end_time), breaks),
+                &rules,
+            )
+        };
+
+        // 5 hours worked doesn't need a meal break
+        assert_eq!(violations("2024-07-01 13:00", &[]), []);
+        assert_eq!(
+            violations("2024-07-01 13:30", &[]),
+            [BreakViolationKind::MissingMealBreak]
+        );
+        // too short to be a meal break
+        assert_eq!(
+            violations(
+                "2024-07-01 16:00",
+                &[shift_break("2024-07-01 12:00", "2024-07-01 12:15")]
+            ),
+            [BreakViolationKind::MissingMealBreak]
+        );
+        assert_eq!(
+            violations(
+                "2024-07-01 16:00",
+                &[shift_break("2024-07-01 13:30", "2024-07-01 14:00")]
+            ),
+            [BreakViolationKind::LateMealBreak]
+        );
+        assert_eq!(
+            violations(
+                "2024-07-01 16:00",
+                &[shift_break("2024-07-01 12:00", "2024-07-01 12:30")]
+            ),
+            []
+        );
+        // 11 hours worked
+        assert_eq!(
+            violations(
+                "2024-07-01 19:30",
+                &[shift_break("2024-07-01 12:00", "2024-07-01 12:30")]
+            ),
+            [BreakViolationKind::MissingSecondMealBreak]
+        );
+        assert_eq!(
+            violations(
+                "2024-07-01 20:00",
+                &[
+                    shift_break("2024-07-01 12:00", "2024-07-01 12:30"),
+                    shift_break("2024-07-01 18:45", "2024-07-01 19:15"),
+                ]
+            ),
+            [BreakViolationKind::LateSecondMealBreak]
+        );
+        assert_eq!(
+            violations(
+                "2024-07-01 20:00",
+                &[
+                    shift_break("2024-07-01 12:00", "2024-07-01 12:30"),
+                    shift_break("2024-07-01 17:00", "2024-07-01 17:30"),
+                ]
+            ),
+            []
+        );
+    }
+}
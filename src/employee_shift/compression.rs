@@ -0,0 +1,113 @@
+use std::{
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use flate2::bufread::MultiGzDecoder;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression an input can be stored with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression from the first bytes of the input, so compressed inputs are read
+    /// correctly whatever they're named, including from stdin
+    pub fn detect(reader: &mut impl BufRead) -> io::Result<Self> {
+        let start = reader.fill_buf()?;
+        if start.starts_with(&GZIP_MAGIC) {
+            Ok(Compression::Gzip)
+        } else if start.starts_with(&ZSTD_MAGIC) {
+            Ok(Compression::Zstd)
+        } else {
+            Ok(Compression::None)
+        }
+    }
+}
+
+/// Wraps the reader in a decoder when its contents are gzip or zstd compressed
+pub fn decompress<'a>(mut reader: Box<dyn BufRead + 'a>) -> io::Result<Box<dyn BufRead + 'a>> {
+    Ok(match Compression::detect(&mut reader)? {
+        Compression::None => reader,
+        // archives made by concatenating gzip files have several members
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?)),
+    })
+}
+
+/// The path without a `.gz`, `.zst` or `.zstd` extension, so the extension underneath it, like the
+/// `.csv` in `shifts.csv.gz`, can be used to tell the format of the input
+pub fn without_compression_extension(path: &Path) -> PathBuf {
+    let is_compressed = path.extension().is_some_and(|extension| {
+        matches!(
+            extension.to_string_lossy().to_ascii_lowercase().as_str(),
+            "gz" | "zst" | "zstd"
+        )
+    });
+    if is_compressed {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    const CONTENTS: &str = "ShiftID,EmployeeID,StartTime,EndTime\n";
+
+    fn read_to_string(bytes: &[u8]) -> String {
+        let mut contents = String::new();
+        decompress(Box::new(bytes))
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        contents
+    }
+
+    #[test]
+    fn test_decompress() {
+        let mut gzip = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        gzip.write_all(CONTENTS.as_bytes()).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let zstd = zstd::encode_all(CONTENTS.as_bytes(), 0).unwrap();
+
+        assert_eq!(
+            Compression::detect(&mut &gzip[..]).unwrap(),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::detect(&mut &zstd[..]).unwrap(),
+            Compression::Zstd
+        );
+        assert_eq!(read_to_string(&gzip), CONTENTS);
+        assert_eq!(read_to_string(&zstd), CONTENTS);
+        assert_eq!(read_to_string(CONTENTS.as_bytes()), CONTENTS);
+        assert_eq!(read_to_string(b""), "");
+    }
+
+    #[test]
+    fn test_without_compression_extension() {
+        assert_eq!(
+            without_compression_extension(Path::new("shifts.csv.gz")),
+            Path::new("shifts.csv")
+        );
+        assert_eq!(
+            without_compression_extension(Path::new("shifts.ndjson.ZST")),
+            Path::new("shifts.ndjson")
+        );
+        assert_eq!(
+            without_compression_extension(Path::new("shifts.json")),
+            Path::new("shifts.json")
+        );
+    }
+}
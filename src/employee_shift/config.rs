@@ -0,0 +1,615 @@
+use std::{collections::HashMap, io::BufRead, path::Path, str::FromStr};
+
+use anyhow::{bail, Context, Error};
+use chrono::{NaiveTime, Weekday};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::employee_shift::{compression::without_compression_extension, employee::EmployeeShift};
+
+/// Longest rest between shifts that can be required, a week
+const MAX_MINIMUM_REST_HOURS: f64 = 7. * 24.;
+
+/// Settings that control how shifts are bucketed and summarized.
+///
+/// Can be built in code, loaded from a JSON config file with [`SummaryConfig::from_file`],
+/// or overridden from the command line.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct SummaryConfig {
+    /// IANA time zone that week boundaries and `StartOfWeek` dates are computed in, unless the
+    /// shift or employee has its own
+    pub time_zone: Tz,
+    /// Time zone for employees working at a location outside of the default time zone
+    pub employee_time_zones: HashMap<u64, Tz>,
+    /// When each employee's workweek starts, in their local time
+    pub work_week: WorkWeek,
+    /// How many hours in a workweek are paid at each rate
+    pub overtime_policy: OvertimePolicy,
+    /// Format of the input file. When unset it's detected from the file extension.
+    pub input_format: Option<InputFormat>,
+    /// Keys or column names the input uses for the shift fields, when they aren't the usual ones
+    pub field_names: FieldNames,
+    /// What happens when a record in the input isn't a valid shift
+    pub validation_mode: ValidationMode,
+    /// Shifts longer than this are implausible and treated as invalid shifts
+    pub max_shift_hours: Option<f64>,
+    /// How punch times are rounded before the hours between them are added up
+    pub rounding: RoundingPolicy,
+    /// Unpaid break deducted from long shifts that don't have any breaks recorded
+    pub automatic_break: Option<AutomaticBreak>,
+    /// Meal breaks shifts are checked for. Shifts that don't get them are reported as break
+    /// violations, but their hours are still counted.
+    pub break_rules: Option<BreakRules>,
+    /// Hours of rest an employee is owed between the end of one shift and the start of the next.
+    /// Shifts starting sooner are reported as rest violations.
+    pub minimum_rest_hours: Option<f64>,
+    /// How the start and end times of shifts and their breaks are written in the input
+    pub timestamp_format: TimestampFormat,
+    /// Which time is meant by a local timestamp that happens twice when the clocks go back
+    pub ambiguous_times: AmbiguousTimePolicy,
+}
+
+/// When meal breaks are owed during a shift. The defaults are California's rules: a meal break of
+/// at least 30 minutes before the end of the 5th hour of work, and a second one before the end of
+/// the 10th hour.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct BreakRules {
+    /// Shifts with more hours worked than this need a meal break starting before this many hours
+    /// have been worked
+    pub first_meal_break_hours: f64,
+    /// Shifts with more hours worked than this need a second meal break starting before this many
+    /// hours have been worked
+    pub second_meal_break_hours: Option<f64>,
+    /// Breaks shorter than this many minutes don't count as meal breaks
+    pub minimum_meal_break_minutes: u32,
+}
+
+impl Default for BreakRules {
+    fn default() -> Self {
+        Self {
+            first_meal_break_hours: 5.,
+            second_meal_break_hours: Some(10.),
+            minimum_meal_break_minutes: 30,
+        }
+    }
+}
+
+/// An unpaid break assumed to be taken in the middle of long shifts, such as deducting 30 minutes
+/// from shifts over 6 hours
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct AutomaticBreak {
+    /// Shifts longer than this many hours get the break
+    pub after_hours: f64,
+    /// Length of the break in minutes
+    pub minutes: u32,
+}
+
+impl Default for AutomaticBreak {
+    fn default() -> Self {
+        Self {
+            after_hours: 6.,
+            minutes: 30,
+        }
+    }
+}
+
+/// Time clock rounding applied to a shift's start and end times, in the shift's local time
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingPolicy {
+    /// Hours are counted from the exact punch times
+    #[default]
+    None,
+    /// Round each punch to the nearest multiple of this many minutes, e.g. 5, 6 or 15
+    Nearest(u32),
+    /// The FLSA 7 minute rule: round to the nearest quarter hour, where up to 7 minutes past a
+    /// quarter hour rounds down and 8 minutes or more rounds up
+    SevenMinuteRule,
+    /// Round start times down and end times up to a multiple of this many minutes, so rounding
+    /// never costs the employee time
+    EmployeeFavor(u32),
+}
+
+impl FromStr for RoundingPolicy {
+    type Err = String;
+
+    /// Parses `none`, `seven-minute-rule`, `nearest-<minutes>` or `employee-favor-<minutes>`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let minutes = |minutes: &str| match minutes.parse::<u32>() {
+            Ok(minutes) if minutes > 0 => Ok(minutes),
+            _ => Err(format!(
+                "rounding interval must be a positive number of minutes, got \"{minutes}\""
+            )),
+        };
+
+        match value {
+            "none" => Ok(RoundingPolicy::None),
+            "seven-minute-rule" => Ok(RoundingPolicy::SevenMinuteRule),
+            _ => {
+                if let Some(interval) = value.strip_prefix("nearest-") {
+                    Ok(RoundingPolicy::Nearest(minutes(interval)?))
+                } else if let Some(interval) = value.strip_prefix("employee-favor-") {
+                    Ok(RoundingPolicy::EmployeeFavor(minutes(interval)?))
+                } else {
+                    Err(format!(
+                        "unknown rounding policy \"{value}\", expected none, seven-minute-rule, \
+                         nearest-<minutes> or employee-favor-<minutes>"
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Format shifts are read from
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// A JSON array of shift objects
+    Json,
+    /// Newline delimited JSON with one shift object per line, read a line at a time
+    Ndjson,
+    /// CSV with a header row and the same column names as the JSON keys
+    Csv,
+}
+
+impl InputFormat {
+    /// Files ending in `.csv` are CSV, files ending in `.ndjson` or `.jsonl` are NDJSON, and
+    /// anything else is expected to be JSON. The extension of a compressed file is the one before
+    /// `.gz` or `.zst`.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = without_compression_extension(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("csv") => InputFormat::Csv,
+            Some("ndjson" | "jsonl") => InputFormat::Ndjson,
+            _ => InputFormat::Json,
+        }
+    }
+
+    /// Detects the format of an input without a name, like stdin, from its first non-whitespace
+    /// character. An input starting with `[` is a JSON array and one starting with `{` is NDJSON.
+    /// Anything else, such as CSV, has to be given a format.
+    pub fn detect(reader: &mut impl BufRead) -> Result<Self, Error> {
+        let start = reader.fill_buf()?;
+        match start.iter().find(|byte| !byte.is_ascii_whitespace()) {
+            Some(b'[') => Ok(InputFormat::Json),
+            Some(b'{') => Ok(InputFormat::Ndjson),
+            _ => bail!(
+                "the input doesn't start with [ or {{, so its format can't be detected. Pass \
+                 --input-format to give it, such as --input-format csv"
+            ),
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(InputFormat::Json),
+            "ndjson" => Ok(InputFormat::Ndjson),
+            "csv" => Ok(InputFormat::Csv),
+            _ => Err(format!(
+                "unknown input format \"{value}\", expected json, ndjson or csv"
+            )),
+        }
+    }
+}
+
+/// Names an input uses for the shift fields instead of the usual ones, like `clock_in` for
+/// `StartTime`. They're JSON keys or CSV column names, and fields left unset keep their usual names.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct FieldNames {
+    #[serde(rename = "ShiftID")]
+    pub shift_id: Option<String>,
+    #[serde(rename = "EmployeeID")]
+    pub employee_id: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub time_zone: Option<String>,
+    pub breaks: Option<String>,
+}
+
+impl FieldNames {
+    /// The name used in the input and the usual name of the field it holds, for each field that
+    /// has a different name in the input
+    pub fn renames(&self) -> impl Iterator<Item = (&str, &'static str)> {
+        [
+            (&self.shift_id, "ShiftID"),
+            (&self.employee_id, "EmployeeID"),
+            (&self.start_time, "StartTime"),
+            (&self.end_time, "EndTime"),
+            (&self.time_zone, "TimeZone"),
+            (&self.breaks, "Breaks"),
+        ]
+        .into_iter()
+        .filter_map(|(name, field)| {
+            name.as_deref()
+                .filter(|name| *name != field)
+                .map(|name| (name, field))
+        })
+    }
+
+    /// Whether the input uses the usual names for every field
+    pub fn is_empty(&self) -> bool {
+        self.renames().next().is_none()
+    }
+
+    /// The usual name of the field an input key or column holds
+    pub fn field_name<'a>(&self, name: &'a str) -> &'a str {
+        self.renames()
+            .find(|(renamed, _)| *renamed == name)
+            .map_or(name, |(_, field)| field)
+    }
+}
+
+/// How timestamps are written in the input. Timestamps without an offset are local times in the
+/// shift's time zone, or the employee's or default time zone when the shift doesn't have one.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub enum TimestampFormat {
+    /// RFC 3339 timestamps with any offset, like `2024-07-03T06:00:00-05:00`
+    #[default]
+    Rfc3339,
+    /// Local timestamps without an offset, like `2024-07-03 06:00:00`. Timestamps that have an
+    /// offset are accepted as well.
+    Local,
+    /// Seconds since the Unix epoch, which can have a fractional part
+    EpochSeconds,
+    /// Milliseconds since the Unix epoch
+    EpochMilliseconds,
+    /// A strftime pattern such as `%m/%d/%Y %H:%M`. Patterns without an offset are local times.
+    Pattern(String),
+}
+
+impl TimestampFormat {
+    /// What a timestamp in this format looks like, for error messages
+    pub fn description(&self) -> String {
+        match self {
+            TimestampFormat::Rfc3339 => "an RFC 3339 timestamp".to_string(),
+            TimestampFormat::Local => "a timestamp like 2024-07-03 06:00:00".to_string(),
+            TimestampFormat::EpochSeconds => "a number of seconds since the Unix epoch".to_string(),
+            TimestampFormat::EpochMilliseconds => {
+                "a number of milliseconds since the Unix epoch".to_string()
+            }
+            TimestampFormat::Pattern(pattern) => format!("a timestamp matching {pattern:?}"),
+        }
+    }
+}
+
+impl FromStr for TimestampFormat {
+    type Err = String;
+
+    /// Parses `rfc3339`, `local`, `epoch-seconds`, `epoch-milliseconds`, or a strftime pattern
+    /// containing at least one `%`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "rfc3339" => Ok(TimestampFormat::Rfc3339),
+            "local" => Ok(TimestampFormat::Local),
+            "epoch-seconds" => Ok(TimestampFormat::EpochSeconds),
+            "epoch-milliseconds" => Ok(TimestampFormat::EpochMilliseconds),
+            _ if value.contains('%') => Ok(TimestampFormat::Pattern(value.to_string())),
+            _ => Err(format!(
+                "unknown timestamp format \"{value}\", expected rfc3339, local, epoch-seconds, \
+                 epoch-milliseconds or a strftime pattern like \"%m/%d/%Y %H:%M\""
+            )),
+        }
+    }
+}
+
+/// Which time is meant by a local timestamp that happens twice, like 1:30 am on the night the
+/// clocks go back an hour
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmbiguousTimePolicy {
+    /// Treat the timestamp as invalid, since there's no telling which time was meant
+    #[default]
+    Reject,
+    /// The first time, before the clocks go back
+    Earliest,
+    /// The second time, after the clocks go back
+    Latest,
+}
+
+impl FromStr for AmbiguousTimePolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "reject" => Ok(AmbiguousTimePolicy::Reject),
+            "earliest" => Ok(AmbiguousTimePolicy::Earliest),
+            "latest" => Ok(AmbiguousTimePolicy::Latest),
+            _ => Err(format!(
+                "unknown ambiguous time policy \"{value}\", expected reject, earliest or latest"
+            )),
+        }
+    }
+}
+
+/// Decides what happens when a record in the input isn't a valid shift
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum ValidationMode {
+    /// Stop and return the first error
+    #[default]
+    Strict,
+    /// Skip invalid records and collect them into a [`ValidationReport`](crate::employee_shift::error::ValidationReport)
+    Report,
+}
+
+/// Weekly hour thresholds above which hours stop being regular hours
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct OvertimePolicy {
+    /// Hours worked in a week beyond this are overtime hours
+    pub weekly_overtime_threshold: f64,
+    /// Hours worked in a week beyond this are double time hours instead of overtime hours. When
+    /// unset, every hour beyond the overtime threshold is an overtime hour.
+    pub weekly_double_time_threshold: Option<f64>,
+    /// Overtime owed for long workdays regardless of the weekly total
+    pub daily: Option<DailyOvertimeRules>,
+    /// Premium pay for the seventh consecutive workday when every day of the workweek is worked
+    pub seventh_day: Option<SeventhDayRule>,
+}
+
+impl Default for OvertimePolicy {
+    fn default() -> Self {
+        Self {
+            weekly_overtime_threshold: 40.,
+            weekly_double_time_threshold: None,
+            daily: None,
+            seventh_day: None,
+        }
+    }
+}
+
+impl OvertimePolicy {
+    /// California's rules: overtime after 8 hours in a workday or 40 hours in a workweek, double
+    /// time after 12 hours in a workday, and on the seventh consecutive workday overtime for the
+    /// first 8 hours and double time after that
+    pub fn california() -> Self {
+        Self {
+            weekly_overtime_threshold: 40.,
+            weekly_double_time_threshold: None,
+            daily: Some(DailyOvertimeRules {
+                overtime_threshold: 8.,
+                double_time_threshold: Some(12.),
+            }),
+            seventh_day: Some(SeventhDayRule::default()),
+        }
+    }
+}
+
+/// Daily hour thresholds above which hours stop being regular hours
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DailyOvertimeRules {
+    /// Hours worked in a workday beyond this are overtime hours
+    pub overtime_threshold: f64,
+    /// Hours worked in a workday beyond this are double time hours instead of overtime hours
+    #[serde(default)]
+    pub double_time_threshold: Option<f64>,
+}
+
+/// The fixed and recurring 7 day period hours are totaled over, such as the FLSA workweek
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct WorkWeek {
+    pub start_day: Weekday,
+    pub start_time: NaiveTime,
+}
+
+impl Default for WorkWeek {
+    fn default() -> Self {
+        Self {
+            start_day: Weekday::Sun,
+            start_time: NaiveTime::MIN,
+        }
+    }
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            time_zone: chrono_tz::US::Central,
+            employee_time_zones: HashMap::new(),
+            work_week: WorkWeek::default(),
+            overtime_policy: OvertimePolicy::default(),
+            input_format: None,
+            field_names: FieldNames::default(),
+            validation_mode: ValidationMode::default(),
+            max_shift_hours: Some(24.),
+            rounding: RoundingPolicy::default(),
+            automatic_break: None,
+            break_rules: None,
+            minimum_rest_hours: None,
+            timestamp_format: TimestampFormat::default(),
+            ambiguous_times: AmbiguousTimePolicy::default(),
+        }
+    }
+}
+
+/// Checks that a number of hours from the config or the command line is a finite number that isn't
+/// negative
+fn check_hours(name: &str, hours: f64) -> Result<(), Error> {
+    if !hours.is_finite() || hours < 0. {
+        bail!("{name} must be a number of hours that isn't negative, got {hours}");
+    }
+
+    Ok(())
+}
+
+impl SummaryConfig {
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open config file: {}", path.to_string_lossy()))?;
+
+        serde_json::from_reader(std::io::BufReader::new(file))
+            .with_context(|| format!("Failed to parse config file: {}", path.to_string_lossy()))
+    }
+
+    /// Checks that the hour values make sense, after the config file has been read and the
+    /// command line flags applied
+    pub fn validate(&self) -> Result<(), Error> {
+        let overtime_policy = &self.overtime_policy;
+        check_hours(
+            "OvertimePolicy.WeeklyOvertimeThreshold",
+            overtime_policy.weekly_overtime_threshold,
+        )?;
+        if let Some(threshold) = overtime_policy.weekly_double_time_threshold {
+            check_hours("OvertimePolicy.WeeklyDoubleTimeThreshold", threshold)?;
+        }
+        if let Some(daily) = &overtime_policy.daily {
+            check_hours(
+                "OvertimePolicy.Daily.OvertimeThreshold",
+                daily.overtime_threshold,
+            )?;
+            if let Some(threshold) = daily.double_time_threshold {
+                check_hours("OvertimePolicy.Daily.DoubleTimeThreshold", threshold)?;
+            }
+        }
+        if let Some(seventh_day) = &overtime_policy.seventh_day {
+            check_hours(
+                "OvertimePolicy.SeventhDay.OvertimeThreshold",
+                seventh_day.overtime_threshold,
+            )?;
+            if let Some(threshold) = seventh_day.double_time_threshold {
+                check_hours("OvertimePolicy.SeventhDay.DoubleTimeThreshold", threshold)?;
+            }
+        }
+        if let Some(max_shift_hours) = self.max_shift_hours {
+            check_hours("MaxShiftHours", max_shift_hours)?;
+        }
+        if let Some(minimum_rest_hours) = self.minimum_rest_hours {
+            check_hours("MinimumRestHours", minimum_rest_hours)?;
+            if minimum_rest_hours > MAX_MINIMUM_REST_HOURS {
+                bail!(
+                    "MinimumRestHours must be between 0 and {MAX_MINIMUM_REST_HOURS}, got \
+                     {minimum_rest_hours}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a JSON object mapping employee ids to IANA time zones, such as
+    /// `{ "41488322": "America/Los_Angeles" }`, and adds it to the employee time zones.
+    pub fn load_employee_time_zones(&mut self, path: &Path) -> Result<(), Error> {
+        let file = std::fs::File::open(path).with_context(|| {
+            format!(
+                "Failed to open employee time zones file: {}",
+                path.to_string_lossy()
+            )
+        })?;
+
+        let time_zones: HashMap<u64, Tz> = serde_json::from_reader(std::io::BufReader::new(file))
+            .with_context(|| {
+            format!(
+                "Failed to parse employee time zones file: {}",
+                path.to_string_lossy()
+            )
+        })?;
+        self.employee_time_zones.extend(time_zones);
+
+        Ok(())
+    }
+
+    /// The time zone a shift's weeks are computed in. A time zone on the shift itself wins over
+    /// the employee's time zone, which wins over the default time zone.
+    pub fn time_zone_for_shift(&self, shift: &EmployeeShift) -> Tz {
+        self.time_zone_for_employee(shift.employee_id, shift.time_zone)
+    }
+
+    /// The time zone for one of the employee's shifts, given the shift's own time zone if it has one
+    pub fn time_zone_for_employee(&self, employee_id: u64, shift_time_zone: Option<Tz>) -> Tz {
+        shift_time_zone
+            .or_else(|| self.employee_time_zones.get(&employee_id).copied())
+            .unwrap_or(self.time_zone)
+    }
+}
+
+/// Hour thresholds used instead of the daily rules on the seventh consecutive workday
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct SeventhDayRule {
+    /// Hours worked on the seventh day beyond this are overtime hours
+    pub overtime_threshold: f64,
+    /// Hours worked on the seventh day beyond this are double time hours
+    pub double_time_threshold: Option<f64>,
+}
+
+impl Default for SeventhDayRule {
+    fn default() -> Self {
+        Self {
+            overtime_threshold: 0.,
+            double_time_threshold: Some(8.),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_overtime_thresholds() {
+        assert!(SummaryConfig::default().validate().is_ok());
+        let with_overtime_policy = |overtime_policy| SummaryConfig {
+            overtime_policy,
+            ..SummaryConfig::default()
+        };
+        assert!(with_overtime_policy(OvertimePolicy::california())
+            .validate()
+            .is_ok());
+        assert!(with_overtime_policy(OvertimePolicy {
+            weekly_overtime_threshold: -5.,
+            ..OvertimePolicy::default()
+        })
+        .validate()
+        .is_err());
+        assert!(with_overtime_policy(OvertimePolicy {
+            weekly_double_time_threshold: Some(f64::INFINITY),
+            ..OvertimePolicy::default()
+        })
+        .validate()
+        .is_err());
+        assert!(with_overtime_policy(OvertimePolicy {
+            daily: Some(DailyOvertimeRules {
+                overtime_threshold: f64::NAN,
+                double_time_threshold: None,
+            }),
+            ..OvertimePolicy::default()
+        })
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_max_shift_hours() {
+        let with_max_shift_hours = |max_shift_hours| SummaryConfig {
+            max_shift_hours,
+            ..SummaryConfig::default()
+        };
+        assert!(with_max_shift_hours(None).validate().is_ok());
+        assert!(with_max_shift_hours(Some(1000.)).validate().is_ok());
+        assert!(with_max_shift_hours(Some(f64::NAN)).validate().is_err());
+        assert!(with_max_shift_hours(Some(-1.)).validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_minimum_rest_hours() {
+        let with_minimum_rest = |minimum_rest_hours| SummaryConfig {
+            minimum_rest_hours: Some(minimum_rest_hours),
+            ..SummaryConfig::default()
+        };
+        assert!(with_minimum_rest(0.).validate().is_ok());
+        assert!(with_minimum_rest(168.).validate().is_ok());
+        assert!(with_minimum_rest(-1.).validate().is_err());
+        assert!(with_minimum_rest(1e13).validate().is_err());
+        assert!(with_minimum_rest(f64::NAN).validate().is_err());
+        assert!(with_minimum_rest(f64::INFINITY).validate().is_err());
+    }
+}
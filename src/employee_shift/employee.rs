@@ -1,8 +1,40 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::employee_shift::hours::Hours;
 
 #[derive(Deserialize, Debug)]
 pub struct RawEmployeeShift {
+    #[serde(rename(deserialize = "ShiftID"))]
+    pub shift_id: u64,
+    #[serde(rename(deserialize = "EmployeeID"))]
+    pub employee_id: u64,
+    #[serde(rename(deserialize = "StartTime"), deserialize_with = "timestamp")]
+    pub start_time: String,
+    #[serde(rename(deserialize = "EndTime"), deserialize_with = "timestamp")]
+    pub end_time: String,
+    #[serde(rename(deserialize = "TimeZone"), default)]
+    pub time_zone: Option<String>,
+    #[serde(rename(deserialize = "Breaks"), default)]
+    pub breaks: Vec<RawBreak>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawBreak {
+    #[serde(rename(deserialize = "StartTime"), deserialize_with = "timestamp")]
+    pub start_time: String,
+    #[serde(rename(deserialize = "EndTime"), deserialize_with = "timestamp")]
+    pub end_time: String,
+}
+
+/// A shift read from a row of CSV. Timestamps are read as plain strings, since the csv crate would
+/// otherwise guess that all digit values like `070320240600` are numbers and drop their leading
+/// zeros. Breaks can't be given in CSV.
+#[derive(Deserialize, Debug)]
+pub struct RawCsvEmployeeShift {
     #[serde(rename(deserialize = "ShiftID"))]
     pub shift_id: u64,
     #[serde(rename(deserialize = "EmployeeID"))]
@@ -11,26 +43,219 @@ pub struct RawEmployeeShift {
     pub start_time: String,
     #[serde(rename(deserialize = "EndTime"))]
     pub end_time: String,
+    #[serde(rename(deserialize = "TimeZone"), default)]
+    pub time_zone: Option<String>,
+}
+
+impl From<RawCsvEmployeeShift> for RawEmployeeShift {
+    fn from(shift: RawCsvEmployeeShift) -> Self {
+        Self {
+            shift_id: shift.shift_id,
+            employee_id: shift.employee_id,
+            start_time: shift.start_time,
+            end_time: shift.end_time,
+            time_zone: shift.time_zone,
+            breaks: vec![],
+        }
+    }
+}
+
+/// Reads a JSON timestamp as it's written, whether it's a string or a number like epoch seconds.
+/// It's parsed later, once the format and time zone it's in are known.
+fn timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamp {
+        Text(String),
+        Integer(i64),
+        Float(f64),
+    }
+
+    Ok(match Timestamp::deserialize(deserializer)? {
+        Timestamp::Text(text) => text,
+        Timestamp::Integer(integer) => integer.to_string(),
+        Timestamp::Float(float) => float.to_string(),
+    })
 }
 
 #[derive(Debug)]
 pub struct EmployeeShift {
+    /// Name of the file the shift was read from, or `stdin`
+    pub file: Arc<str>,
+    /// Position of the shift's record in its file, starting from 1
+    pub record: usize,
+    pub shift_id: u64,
+    pub employee_id: u64,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub time_zone: Option<Tz>,
+    /// Unpaid breaks taken during the shift
+    pub breaks: Vec<Break>,
+}
+
+/// An unpaid break, such as a meal break, taken during a shift
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Break {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+impl EmployeeShift {
+    /// Whether both shifts have the same data, regardless of where they are in the input
+    pub fn has_same_data(&self, other: &EmployeeShift) -> bool {
+        self.shift_id == other.shift_id
+            && self.employee_id == other.employee_id
+            && self.start_time == other.start_time
+            && self.end_time == other.end_time
+            && self.time_zone == other.time_zone
+            && self.breaks == other.breaks
+    }
+}
+
+#[cfg(test)]
+impl EmployeeShift {
+    /// A shift worked by employee 1 without a time zone or breaks, for tests. Its record number is
+    /// its shift id.
+    pub fn test_shift(shift_id: u64, start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Self {
+        Self {
+            file: Arc::from("test.json"),
+            record: shift_id as usize,
+            shift_id,
+            employee_id: 1,
+            start_time,
+            end_time,
+            time_zone: None,
+            breaks: vec![],
+        }
+    }
+}
+
+/// A shift that was counted towards an employee's hours, with both the punch times from the input
+/// and the rounded times its hours were computed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShiftDetail {
     pub shift_id: u64,
     pub employee_id: u64,
+    /// Time zone the shift was rounded and bucketed into weeks in
+    pub time_zone: Tz,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
+    pub rounded_start_time: DateTime<Utc>,
+    pub rounded_end_time: DateTime<Utc>,
+    /// Paid hours, not counting unpaid breaks
+    pub hours: Hours,
+    pub unpaid_break_hours: Hours,
 }
 
-#[derive(Serialize, Debug)]
+/// Hours an employee worked in a single workweek. See [`output`](crate::employee_shift::output)
+/// for how it's written out.
+#[derive(Debug)]
 pub struct EmployeeShiftSummary {
-    #[serde(rename(serialize = "EmployeeID"))]
     pub employee_id: u64,
-    #[serde(rename(serialize = "StartOfWeek"))]
     pub start_of_week: String,
-    #[serde(rename(serialize = "RegularHours"))]
-    pub regular_hours: f64,
-    #[serde(rename(serialize = "OvertimeHours"))]
-    pub overtime_hours: f64,
-    #[serde(rename(serialize = "InvalidShifts"))]
-    pub invalid_shifts: Vec<u64>,
+    pub regular_hours: Hours,
+    pub overtime_hours: Hours,
+    pub double_time_hours: Hours,
+    pub seventh_day_hours: Hours,
+    pub invalid_shifts: Vec<InvalidShift>,
+    /// Shifts started this week that didn't get the meal breaks the break rules require
+    pub break_violations: Vec<BreakViolation>,
+    /// Shifts started this week without enough rest after the shift before them
+    pub rest_violations: Vec<RestViolation>,
+    /// Total premium hours owed for the rest violations
+    pub rest_premium_hours: Hours,
+    pub days: Vec<DailyHoursSummary>,
+}
+
+impl EmployeeShiftSummary {
+    pub fn new(employee_id: u64, start_of_week: NaiveDate) -> Self {
+        Self {
+            employee_id,
+            start_of_week: start_of_week.to_string(),
+            regular_hours: Hours::ZERO,
+            overtime_hours: Hours::ZERO,
+            double_time_hours: Hours::ZERO,
+            seventh_day_hours: Hours::ZERO,
+            invalid_shifts: vec![],
+            break_violations: vec![],
+            rest_violations: vec![],
+            rest_premium_hours: Hours::ZERO,
+            days: vec![],
+        }
+    }
+}
+
+/// A shift that started before the employee had enough rest after their previous shift
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestViolation {
+    pub shift_id: u64,
+    pub previous_shift_id: u64,
+    /// Time between the end of the previous shift and the start of this one
+    pub rest_hours: Hours,
+    /// Hours of this shift worked during what should have been rest
+    pub premium_hours: Hours,
+}
+
+/// A shift that was counted but didn't get a meal break it was owed
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BreakViolation {
+    #[serde(rename(serialize = "ShiftID"))]
+    pub shift_id: u64,
+    #[serde(rename(serialize = "Violation"))]
+    pub violation: BreakViolationKind,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakViolationKind {
+    /// The shift was long enough to need a meal break but didn't have one
+    MissingMealBreak,
+    /// The first meal break started after too many hours had already been worked
+    LateMealBreak,
+    /// The shift was long enough to need a second meal break but didn't have one
+    MissingSecondMealBreak,
+    /// The second meal break started after too many hours had already been worked
+    LateSecondMealBreak,
+}
+
+/// A shift that wasn't counted towards any hours and why
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct InvalidShift {
+    #[serde(rename(serialize = "ShiftID"))]
+    pub shift_id: u64,
+    #[serde(rename(serialize = "Reason"))]
+    pub reason: InvalidShiftReason,
+    /// The other shifts this shift conflicts with, such as the shifts it overlaps
+    #[serde(
+        rename(serialize = "ConflictingShiftIDs"),
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub conflicting_shift_ids: Vec<u64>,
+}
+
+impl InvalidShift {
+    pub fn new(shift_id: u64, reason: InvalidShiftReason) -> Self {
+        Self {
+            shift_id,
+            reason,
+            conflicting_shift_ids: vec![],
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum InvalidShiftReason {
+    Overlapping,
+    EndBeforeStart,
+    ZeroLength,
+    TooLong,
+    DuplicateShiftID,
+}
+
+/// Hours worked during a single workday of the week
+#[derive(Debug)]
+pub struct DailyHoursSummary {
+    pub date: String,
+    pub regular_hours: Hours,
+    pub overtime_hours: Hours,
+    pub double_time_hours: Hours,
 }
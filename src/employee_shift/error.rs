@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use chrono_tz::Tz;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+use crate::employee_shift::{config::TimestampFormat, employee::InvalidShiftReason};
+
+/// Why a shift record in the input couldn't be used
+#[derive(Error, Debug)]
+pub enum ShiftError {
+    #[error("{file} record {record} is not a valid shift: {message}")]
+    Parse {
+        file: Arc<str>,
+        record: usize,
+        message: String,
+    },
+    #[error(
+        "{file} record {record}, shift {shift_id}: {field} {value:?} is not {}",
+        .format.description()
+    )]
+    BadTimestamp {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        field: &'static str,
+        value: String,
+        format: TimestampFormat,
+    },
+    #[error("{file} record {record}, shift {shift_id}: {field} {value:?} happens twice in {time_zone} when the clocks go back")]
+    AmbiguousTimestamp {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        field: &'static str,
+        value: String,
+        time_zone: Tz,
+    },
+    #[error("{file} record {record}, shift {shift_id}: {field} {value:?} doesn't exist in {time_zone} because the clocks go forward past it")]
+    NonexistentTimestamp {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        field: &'static str,
+        value: String,
+        time_zone: Tz,
+    },
+    #[error("{file} record {record}, shift {shift_id}: {value:?} is not an IANA time zone")]
+    BadTimeZone {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        value: String,
+    },
+    #[error("{file} record {record}, shift {shift_id}: break {break_number} doesn't end after it starts within the shift")]
+    BadBreak {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        /// Position of the break in the shift's breaks, starting from 1
+        break_number: usize,
+    },
+    #[error("{file} record {record}, shift {shift_id}: ends before it starts")]
+    EndBeforeStart {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+    },
+    #[error("{file} record {record}, shift {shift_id}: starts and ends at the same time")]
+    ZeroLength {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+    },
+    #[error("{file} record {record}, shift {shift_id}: is {hours} hours long, longer than the maximum of {max_hours} hours")]
+    TooLong {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        hours: f64,
+        max_hours: f64,
+    },
+    #[error("{file} record {record}, shift {shift_id}: exact duplicate of {first_file} record {first_record}")]
+    DuplicateRecord {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        first_file: Arc<str>,
+        first_record: usize,
+    },
+    #[error("{file} record {record}, shift {shift_id}: has the same shift id as {first_file} record {first_record} but different data")]
+    ConflictingDuplicate {
+        file: Arc<str>,
+        record: usize,
+        shift_id: u64,
+        first_file: Arc<str>,
+        first_record: usize,
+    },
+}
+
+impl ShiftError {
+    /// File the record was read from
+    pub fn file(&self) -> &str {
+        match self {
+            ShiftError::Parse { file, .. }
+            | ShiftError::BadTimestamp { file, .. }
+            | ShiftError::AmbiguousTimestamp { file, .. }
+            | ShiftError::NonexistentTimestamp { file, .. }
+            | ShiftError::BadTimeZone { file, .. }
+            | ShiftError::BadBreak { file, .. }
+            | ShiftError::EndBeforeStart { file, .. }
+            | ShiftError::ZeroLength { file, .. }
+            | ShiftError::TooLong { file, .. }
+            | ShiftError::DuplicateRecord { file, .. }
+            | ShiftError::ConflictingDuplicate { file, .. } => file,
+        }
+    }
+
+    /// Position of the record in its file, starting from 1
+    pub fn record(&self) -> usize {
+        match self {
+            ShiftError::Parse { record, .. }
+            | ShiftError::BadTimestamp { record, .. }
+            | ShiftError::AmbiguousTimestamp { record, .. }
+            | ShiftError::NonexistentTimestamp { record, .. }
+            | ShiftError::BadTimeZone { record, .. }
+            | ShiftError::BadBreak { record, .. }
+            | ShiftError::EndBeforeStart { record, .. }
+            | ShiftError::ZeroLength { record, .. }
+            | ShiftError::TooLong { record, .. }
+            | ShiftError::DuplicateRecord { record, .. }
+            | ShiftError::ConflictingDuplicate { record, .. } => *record,
+        }
+    }
+
+    pub fn shift_id(&self) -> Option<u64> {
+        match self {
+            ShiftError::Parse { .. } => None,
+            ShiftError::BadTimestamp { shift_id, .. }
+            | ShiftError::AmbiguousTimestamp { shift_id, .. }
+            | ShiftError::NonexistentTimestamp { shift_id, .. }
+            | ShiftError::BadTimeZone { shift_id, .. }
+            | ShiftError::BadBreak { shift_id, .. }
+            | ShiftError::EndBeforeStart { shift_id, .. }
+            | ShiftError::ZeroLength { shift_id, .. }
+            | ShiftError::TooLong { shift_id, .. }
+            | ShiftError::DuplicateRecord { shift_id, .. }
+            | ShiftError::ConflictingDuplicate { shift_id, .. } => Some(*shift_id),
+        }
+    }
+
+    /// Reason a shift with this error is listed under `InvalidShifts`. Errors that stop a record
+    /// from being read at all have no reason since the shift never makes it into a summary.
+    pub fn invalid_shift_reason(&self) -> Option<InvalidShiftReason> {
+        match self {
+            ShiftError::EndBeforeStart { .. } => Some(InvalidShiftReason::EndBeforeStart),
+            ShiftError::ZeroLength { .. } => Some(InvalidShiftReason::ZeroLength),
+            ShiftError::TooLong { .. } => Some(InvalidShiftReason::TooLong),
+            ShiftError::ConflictingDuplicate { .. } => Some(InvalidShiftReason::DuplicateShiftID),
+            ShiftError::Parse { .. }
+            | ShiftError::BadTimestamp { .. }
+            | ShiftError::AmbiguousTimestamp { .. }
+            | ShiftError::NonexistentTimestamp { .. }
+            | ShiftError::BadTimeZone { .. }
+            | ShiftError::BadBreak { .. }
+            | ShiftError::DuplicateRecord { .. } => None,
+        }
+    }
+
+    /// Short code for the kind of error, stable enough to filter on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ShiftError::Parse { .. } => "Parse",
+            ShiftError::BadTimestamp { .. } => "BadTimestamp",
+            ShiftError::AmbiguousTimestamp { .. } => "AmbiguousTimestamp",
+            ShiftError::NonexistentTimestamp { .. } => "NonexistentTimestamp",
+            ShiftError::BadTimeZone { .. } => "BadTimeZone",
+            ShiftError::BadBreak { .. } => "BadBreak",
+            ShiftError::EndBeforeStart { .. } => "EndBeforeStart",
+            ShiftError::ZeroLength { .. } => "ZeroLength",
+            ShiftError::TooLong { .. } => "TooLong",
+            ShiftError::DuplicateRecord { .. } => "DuplicateRecord",
+            ShiftError::ConflictingDuplicate { .. } => "ConflictingDuplicate",
+        }
+    }
+}
+
+/// Records that were skipped because they weren't valid shifts, and shifts that were summarized
+/// as invalid shifts because their times don't make sense
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub errors: Vec<ShiftError>,
+    /// Problems that were fixed without losing any data, such as exact duplicate records
+    pub warnings: Vec<ShiftError>,
+}
+
+impl ValidationReport {
+    /// Orders errors and warnings by file and then by where the record is in the file
+    pub fn sort(&mut self) {
+        let location = |error: &ShiftError| (error.file().to_string(), error.record());
+        self.errors.sort_by_cached_key(location);
+        self.warnings.sort_by_cached_key(location);
+    }
+}
+
+#[derive(Serialize)]
+struct ValidationReportEntry<'a> {
+    #[serde(rename(serialize = "File"))]
+    file: &'a str,
+    #[serde(rename(serialize = "Record"))]
+    record: usize,
+    #[serde(rename(serialize = "ShiftID"))]
+    shift_id: Option<u64>,
+    #[serde(rename(serialize = "Kind"))]
+    kind: &'a str,
+    #[serde(rename(serialize = "Message"))]
+    message: String,
+}
+
+impl Serialize for ValidationReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            #[serde(rename(serialize = "Errors"))]
+            errors: Vec<ValidationReportEntry<'a>>,
+            #[serde(rename(serialize = "Warnings"))]
+            warnings: Vec<ValidationReportEntry<'a>>,
+        }
+
+        fn entries(errors: &[ShiftError]) -> Vec<ValidationReportEntry<'_>> {
+            errors
+                .iter()
+                .map(|error| ValidationReportEntry {
+                    file: error.file(),
+                    record: error.record(),
+                    shift_id: error.shift_id(),
+                    kind: error.kind(),
+                    message: error.to_string(),
+                })
+                .collect()
+        }
+
+        Report {
+            errors: entries(&self.errors),
+            warnings: entries(&self.warnings),
+        }
+        .serialize(serializer)
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Sub},
+};
+
+use chrono::TimeDelta;
+
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+
+/// An amount of time worked, kept as whole seconds so adding up many shifts is exact. Converted to
+/// fractional hours only when it's written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hours {
+    seconds: i64,
+}
+
+impl Hours {
+    pub const ZERO: Hours = Hours { seconds: 0 };
+    pub const MAX: Hours = Hours { seconds: i64::MAX };
+
+    pub fn from_seconds(seconds: i64) -> Self {
+        Self { seconds }
+    }
+
+    /// Converts a number of hours, such as an overtime threshold from the config, to the nearest
+    /// second. Infinite hours become [`Hours::MAX`].
+    pub fn from_hours(hours: f64) -> Self {
+        // float to int casts saturate, so infinity ends up as i64::MAX
+        Self {
+            seconds: (hours * SECONDS_PER_HOUR as f64).round() as i64,
+        }
+    }
+
+    /// Truncates to whole seconds
+    pub fn from_duration(duration: TimeDelta) -> Self {
+        Self {
+            seconds: duration.num_seconds(),
+        }
+    }
+
+    pub fn seconds(self) -> i64 {
+        self.seconds
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.seconds as f64 / SECONDS_PER_HOUR as f64
+    }
+
+    /// Hours rounded half away from zero to `decimal_places`. The rounding is done on whole
+    /// seconds so values like 0.125 hours round the same way every time. With more decimal places
+    /// than the rounding can be done in, the hours are returned unrounded, since an `f64` can't
+    /// hold that many anyway.
+    pub fn rounded(self, decimal_places: u32) -> f64 {
+        let half_hour = SECONDS_PER_HOUR as i128 / 2;
+        let rounded = 10_i128.checked_pow(decimal_places).and_then(|scale| {
+            let scaled_seconds = (self.seconds as i128).checked_mul(scale)?;
+            let rounded = if scaled_seconds >= 0 {
+                scaled_seconds.checked_add(half_hour)?
+            } else {
+                scaled_seconds.checked_sub(half_hour)?
+            } / SECONDS_PER_HOUR as i128;
+            Some(rounded as f64 / scale as f64)
+        });
+
+        rounded.unwrap_or_else(|| self.as_f64())
+    }
+}
+
+impl Add for Hours {
+    type Output = Hours;
+
+    fn add(self, other: Hours) -> Hours {
+        Hours {
+            seconds: self.seconds.saturating_add(other.seconds),
+        }
+    }
+}
+
+impl AddAssign for Hours {
+    fn add_assign(&mut self, other: Hours) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Hours {
+    type Output = Hours;
+
+    fn sub(self, other: Hours) -> Hours {
+        Hours {
+            seconds: self.seconds.saturating_sub(other.seconds),
+        }
+    }
+}
+
+impl Sum for Hours {
+    fn sum<I: Iterator<Item = Hours>>(iter: I) -> Hours {
+        iter.fold(Hours::ZERO, Add::add)
+    }
+}
+
+/// Compares against a number of hours, so `hours == 8.5` is true for 8 hours and 30 minutes
+impl PartialEq<f64> for Hours {
+    fn eq(&self, hours: &f64) -> bool {
+        self.seconds as f64 == hours * SECONDS_PER_HOUR as f64
+    }
+}
+
+impl fmt::Display for Hours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounded_hours() {
+        assert_eq!(Hours::from_seconds(30 * 60).rounded(2), 0.5);
+        // 7 minutes 30 seconds is exactly 0.125 hours
+        assert_eq!(Hours::from_seconds(7 * 60 + 30).rounded(2), 0.13);
+        assert_eq!(Hours::from_seconds(20 * 60).rounded(2), 0.33);
+        assert_eq!(Hours::from_seconds(20 * 60).rounded(4), 0.3333);
+        assert_eq!(Hours::from_seconds(40 * 60).rounded(0), 1.);
+    }
+
+    #[test]
+    fn test_rounding_to_too_many_decimal_places_does_not_overflow() {
+        assert_eq!(Hours::from_seconds(30 * 60).rounded(39), 0.5);
+        assert_eq!(Hours::MAX.rounded(30), Hours::MAX.as_f64());
+        assert_eq!(
+            Hours::from_seconds(i64::MIN).rounded(30),
+            i64::MIN as f64 / 3600.
+        );
+    }
+
+    #[test]
+    fn test_adding_many_shifts_is_exact() {
+        // a tenth of an hour can't be represented exactly as a float, so adding it up as floats
+        // drifts
+        let total: Hours = (0..1000).map(|_| Hours::from_seconds(6 * 60)).sum();
+        assert_eq!(total, 100.);
+        assert_ne!((0..1000).map(|_| 0.1).sum::<f64>(), 100.);
+    }
+}
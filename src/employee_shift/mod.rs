@@ -1,2 +1,11 @@
+pub mod breaks;
+pub mod compression;
+pub mod config;
 pub mod employee;
+pub mod error;
+pub mod hours;
+pub mod output;
+pub mod overtime;
+pub mod rounding;
 pub mod summarize_shifts;
+pub mod timestamps;
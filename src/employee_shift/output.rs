@@ -0,0 +1,499 @@
+use std::str::FromStr;
+
+use anyhow::Error;
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::employee_shift::employee::{
+    BreakViolation, DailyHoursSummary, EmployeeShiftSummary, InvalidShift, ShiftDetail,
+};
+
+/// Separates the shift ids in list columns of CSV output, such as `InvalidShifts`
+const CSV_LIST_DELIMITER: &str = ";";
+
+/// Format summaries are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    /// One row per employee week with the same column names as the JSON keys. Lists of shifts are
+    /// written as shift ids separated by semicolons and the daily hours are left out.
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!(
+                "unknown output format \"{value}\", expected json or csv"
+            )),
+        }
+    }
+}
+
+/// Controls how summaries are written out
+#[derive(Debug, Clone)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    /// Write `InvalidShifts` as a plain list of shift ids, the way older versions of the output
+    /// did, instead of a list of shifts with the reason each one is invalid
+    pub flat_invalid_shifts: bool,
+    /// Number of decimal places hours are rounded to. Hours are only rounded when written out,
+    /// so totals don't drift from the shifts they were added up from.
+    pub precision: u32,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            format: OutputFormat::default(),
+            flat_invalid_shifts: false,
+            precision: 2,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SummaryOutput<'a> {
+    #[serde(rename(serialize = "EmployeeID"))]
+    employee_id: u64,
+    #[serde(rename(serialize = "StartOfWeek"))]
+    start_of_week: &'a str,
+    #[serde(rename(serialize = "RegularHours"))]
+    regular_hours: f64,
+    #[serde(rename(serialize = "OvertimeHours"))]
+    overtime_hours: f64,
+    #[serde(rename(serialize = "DoubleTimeHours"))]
+    double_time_hours: f64,
+    #[serde(rename(serialize = "SeventhDayHours"))]
+    seventh_day_hours: f64,
+    #[serde(rename(serialize = "InvalidShifts"))]
+    invalid_shifts: InvalidShiftsOutput<'a>,
+    /// Only written when there are violations, so the output doesn't change when break rules
+    /// aren't being checked
+    #[serde(
+        rename(serialize = "BreakViolations"),
+        skip_serializing_if = "<[_]>::is_empty"
+    )]
+    break_violations: &'a [BreakViolation],
+    #[serde(
+        rename(serialize = "RestViolationCount"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    rest_violation_count: Option<usize>,
+    #[serde(
+        rename(serialize = "RestPremiumHours"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    rest_premium_hours: Option<f64>,
+    #[serde(
+        rename(serialize = "RestViolations"),
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    rest_violations: Vec<RestViolationOutput>,
+    #[serde(rename(serialize = "Days"))]
+    days: Vec<DailyOutput<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum InvalidShiftsOutput<'a> {
+    Ids(Vec<u64>),
+    Detailed(&'a [InvalidShift]),
+}
+
+#[derive(Serialize)]
+struct RestViolationOutput {
+    #[serde(rename(serialize = "ShiftID"))]
+    shift_id: u64,
+    #[serde(rename(serialize = "PreviousShiftID"))]
+    previous_shift_id: u64,
+    #[serde(rename(serialize = "RestHours"))]
+    rest_hours: f64,
+    #[serde(rename(serialize = "PremiumHours"))]
+    premium_hours: f64,
+}
+
+#[derive(Serialize)]
+struct DailyOutput<'a> {
+    #[serde(rename(serialize = "Date"))]
+    date: &'a str,
+    #[serde(rename(serialize = "RegularHours"))]
+    regular_hours: f64,
+    #[serde(rename(serialize = "OvertimeHours"))]
+    overtime_hours: f64,
+    #[serde(rename(serialize = "DoubleTimeHours"))]
+    double_time_hours: f64,
+}
+
+impl<'a> SummaryOutput<'a> {
+    fn new(summary: &'a EmployeeShiftSummary, options: &OutputOptions) -> Self {
+        let precision = options.precision;
+        let invalid_shifts = if options.flat_invalid_shifts {
+            InvalidShiftsOutput::Ids(
+                summary
+                    .invalid_shifts
+                    .iter()
+                    .map(|invalid_shift| invalid_shift.shift_id)
+                    .collect(),
+            )
+        } else {
+            InvalidShiftsOutput::Detailed(&summary.invalid_shifts)
+        };
+
+        // the rest fields are left out for weeks without rest violations, the same as break
+        // violations
+        let has_rest_violations = !summary.rest_violations.is_empty();
+
+        Self {
+            employee_id: summary.employee_id,
+            start_of_week: &summary.start_of_week,
+            regular_hours: summary.regular_hours.rounded(precision),
+            overtime_hours: summary.overtime_hours.rounded(precision),
+            double_time_hours: summary.double_time_hours.rounded(precision),
+            seventh_day_hours: summary.seventh_day_hours.rounded(precision),
+            invalid_shifts,
+            break_violations: &summary.break_violations,
+            rest_violation_count: has_rest_violations.then_some(summary.rest_violations.len()),
+            rest_premium_hours: has_rest_violations
+                .then(|| summary.rest_premium_hours.rounded(precision)),
+            rest_violations: summary
+                .rest_violations
+                .iter()
+                .map(|violation| RestViolationOutput {
+                    shift_id: violation.shift_id,
+                    previous_shift_id: violation.previous_shift_id,
+                    rest_hours: violation.rest_hours.rounded(precision),
+                    premium_hours: violation.premium_hours.rounded(precision),
+                })
+                .collect(),
+            days: summary
+                .days
+                .iter()
+                .map(|day| DailyOutput::new(day, precision))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> DailyOutput<'a> {
+    fn new(day: &'a DailyHoursSummary, precision: u32) -> Self {
+        Self {
+            date: &day.date,
+            regular_hours: day.regular_hours.rounded(precision),
+            overtime_hours: day.overtime_hours.rounded(precision),
+            double_time_hours: day.double_time_hours.rounded(precision),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ShiftDetailOutput {
+    #[serde(rename(serialize = "ShiftID"))]
+    shift_id: u64,
+    #[serde(rename(serialize = "EmployeeID"))]
+    employee_id: u64,
+    #[serde(rename(serialize = "StartTime"))]
+    start_time: String,
+    #[serde(rename(serialize = "EndTime"))]
+    end_time: String,
+    #[serde(rename(serialize = "RoundedStartTime"))]
+    rounded_start_time: String,
+    #[serde(rename(serialize = "RoundedEndTime"))]
+    rounded_end_time: String,
+    #[serde(rename(serialize = "Hours"))]
+    hours: f64,
+    #[serde(rename(serialize = "UnpaidBreakHours"))]
+    unpaid_break_hours: f64,
+}
+
+impl ShiftDetailOutput {
+    fn new(detail: &ShiftDetail, options: &OutputOptions) -> Self {
+        let local_time = |time: DateTime<Utc>| local_timestamp(time, detail.time_zone);
+        Self {
+            shift_id: detail.shift_id,
+            employee_id: detail.employee_id,
+            start_time: local_time(detail.start_time),
+            end_time: local_time(detail.end_time),
+            rounded_start_time: local_time(detail.rounded_start_time),
+            rounded_end_time: local_time(detail.rounded_end_time),
+            hours: detail.hours.rounded(options.precision),
+            unpaid_break_hours: detail.unpaid_break_hours.rounded(options.precision),
+        }
+    }
+}
+
+/// RFC 3339 timestamp in the shift's local time, so rounding to the local clock is easy to check
+fn local_timestamp(time: DateTime<Utc>, time_zone: Tz) -> String {
+    time.with_timezone(&time_zone)
+        .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+#[derive(Serialize)]
+struct SummaryCsvRow<'a> {
+    #[serde(rename(serialize = "EmployeeID"))]
+    employee_id: u64,
+    #[serde(rename(serialize = "StartOfWeek"))]
+    start_of_week: &'a str,
+    #[serde(rename(serialize = "RegularHours"))]
+    regular_hours: f64,
+    #[serde(rename(serialize = "OvertimeHours"))]
+    overtime_hours: f64,
+    #[serde(rename(serialize = "DoubleTimeHours"))]
+    double_time_hours: f64,
+    #[serde(rename(serialize = "SeventhDayHours"))]
+    seventh_day_hours: f64,
+    #[serde(rename(serialize = "InvalidShifts"))]
+    invalid_shifts: String,
+    #[serde(rename(serialize = "BreakViolations"))]
+    break_violations: String,
+    #[serde(rename(serialize = "RestViolationCount"))]
+    rest_violation_count: usize,
+    #[serde(rename(serialize = "RestPremiumHours"))]
+    rest_premium_hours: f64,
+}
+
+impl<'a> SummaryCsvRow<'a> {
+    fn new(summary: &'a EmployeeShiftSummary, options: &OutputOptions) -> Self {
+        let precision = options.precision;
+        let shift_ids = |shift_ids: &mut dyn Iterator<Item = u64>| {
+            shift_ids
+                .map(|shift_id| shift_id.to_string())
+                .collect::<Vec<_>>()
+                .join(CSV_LIST_DELIMITER)
+        };
+
+        Self {
+            employee_id: summary.employee_id,
+            start_of_week: &summary.start_of_week,
+            regular_hours: summary.regular_hours.rounded(precision),
+            overtime_hours: summary.overtime_hours.rounded(precision),
+            double_time_hours: summary.double_time_hours.rounded(precision),
+            seventh_day_hours: summary.seventh_day_hours.rounded(precision),
+            invalid_shifts: shift_ids(
+                &mut summary
+                    .invalid_shifts
+                    .iter()
+                    .map(|invalid_shift| invalid_shift.shift_id),
+            ),
+            break_violations: shift_ids(
+                &mut summary
+                    .break_violations
+                    .iter()
+                    .map(|violation| violation.shift_id),
+            ),
+            rest_violation_count: summary.rest_violations.len(),
+            rest_premium_hours: summary.rest_premium_hours.rounded(precision),
+        }
+    }
+}
+
+/// Writes summaries in the format the options ask for
+pub fn summaries_to_string(
+    summaries: &[EmployeeShiftSummary],
+    options: &OutputOptions,
+) -> Result<String, Error> {
+    match options.format {
+        OutputFormat::Json => summaries_to_json(summaries, options),
+        OutputFormat::Csv => summaries_to_csv(summaries, options),
+    }
+}
+
+pub fn summaries_to_csv(
+    summaries: &[EmployeeShiftSummary],
+    options: &OutputOptions,
+) -> Result<String, Error> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for summary in summaries {
+        writer.serialize(SummaryCsvRow::new(summary, options))?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+pub fn summaries_to_json(
+    summaries: &[EmployeeShiftSummary],
+    options: &OutputOptions,
+) -> Result<String, Error> {
+    let output = summaries
+        .iter()
+        .map(|summary| SummaryOutput::new(summary, options))
+        .collect::<Vec<_>>();
+
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+/// Writes each counted shift with its original and rounded start and end times
+pub fn shift_details_to_json(
+    shift_details: &[ShiftDetail],
+    options: &OutputOptions,
+) -> Result<String, Error> {
+    let output = shift_details
+        .iter()
+        .map(|detail| ShiftDetailOutput::new(detail, options))
+        .collect::<Vec<_>>();
+
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::employee_shift::{
+        config::{RoundingPolicy, SummaryConfig},
+        summarize_shifts::summarize_shifts_from_file,
+    };
+
+    #[test]
+    fn test_invalid_shifts_with_reasons() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_overlapping_shift.json").unwrap();
+        let summaries = summarize_shifts_from_file(&path, &SummaryConfig::default())
+            .unwrap()
+            .summaries;
+
+        let json: Value = serde_json::from_str(
+            &summaries_to_json(&summaries, &OutputOptions::default()).unwrap(),
+        )
+        .unwrap();
+        let summary = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|summary| summary["StartOfWeek"] == "2024-07-07")
+            .unwrap();
+
+        assert_eq!(
+            summary["InvalidShifts"],
+            serde_json::json!([{
+                "ShiftID": 2663141013u64,
+                "Reason": "Overlapping",
+                "ConflictingShiftIDs": [2663141019u64],
+            }])
+        );
+    }
+
+    #[test]
+    fn test_flat_invalid_shifts() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_overlapping_shift.json").unwrap();
+        let summaries = summarize_shifts_from_file(&path, &SummaryConfig::default())
+            .unwrap()
+            .summaries;
+
+        let options = OutputOptions {
+            flat_invalid_shifts: true,
+            ..OutputOptions::default()
+        };
+        let json: Value =
+            serde_json::from_str(&summaries_to_json(&summaries, &options).unwrap()).unwrap();
+        let summary = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|summary| summary["StartOfWeek"] == "2024-07-07")
+            .unwrap();
+
+        assert_eq!(summary["InvalidShifts"], serde_json::json!([2663141013u64]));
+    }
+
+    #[test]
+    fn test_hours_are_rounded_to_precision() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_seconds.json").unwrap();
+        let summaries = summarize_shifts_from_file(&path, &SummaryConfig::default())
+            .unwrap()
+            .summaries;
+
+        let hours_with_precision = |precision: u32| {
+            let options = OutputOptions {
+                precision,
+                ..OutputOptions::default()
+            };
+            let json: Value =
+                serde_json::from_str(&summaries_to_json(&summaries, &options).unwrap()).unwrap();
+            json[0]["RegularHours"].as_f64().unwrap()
+        };
+
+        // three shifts of 1 hour and 20 seconds each
+        assert_eq!(hours_with_precision(2), 3.02);
+        assert_eq!(hours_with_precision(4), 3.0167);
+        assert_eq!(hours_with_precision(0), 3.);
+    }
+
+    #[test]
+    fn test_shift_details_with_rounded_times() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_rounding.json").unwrap();
+        let config = SummaryConfig {
+            rounding: RoundingPolicy::Nearest(15),
+            ..SummaryConfig::default()
+        };
+        let shift_details = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .shift_details;
+
+        let json: Value = serde_json::from_str(
+            &shift_details_to_json(&shift_details, &OutputOptions::default()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json[0],
+            serde_json::json!({
+                "ShiftID": 1,
+                "EmployeeID": 41488322,
+                "StartTime": "2024-07-01T08:53:00-05:00",
+                "EndTime": "2024-07-01T17:07:00-05:00",
+                "RoundedStartTime": "2024-07-01T09:00:00-05:00",
+                "RoundedEndTime": "2024-07-01T17:00:00-05:00",
+                "Hours": 8.0,
+                "UnpaidBreakHours": 0.0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_summaries_to_csv() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_overlapping_shift.json").unwrap();
+        let mut summaries = summarize_shifts_from_file(&path, &SummaryConfig::default())
+            .unwrap()
+            .summaries;
+        summaries.sort_by(|a, b| a.start_of_week.cmp(&b.start_of_week));
+
+        let options = OutputOptions {
+            format: OutputFormat::Csv,
+            ..OutputOptions::default()
+        };
+        let csv = summaries_to_string(&summaries, &options).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some(
+                "EmployeeID,StartOfWeek,RegularHours,OvertimeHours,DoubleTimeHours,\
+                 SeventhDayHours,InvalidShifts,BreakViolations,RestViolationCount,RestPremiumHours"
+            )
+        );
+        assert_eq!(lines.clone().count(), summaries.len());
+        let week_with_invalid_shift = lines.find(|line| line.contains(",2024-07-07,")).unwrap();
+        assert!(week_with_invalid_shift.contains(",2663141013,"));
+    }
+}
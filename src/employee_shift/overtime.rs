@@ -0,0 +1,138 @@
+use crate::employee_shift::{config::OvertimePolicy, employee::EmployeeShiftSummary, hours::Hours};
+
+/// Splits the hours worked in each summary's week into regular, overtime and double time hours.
+///
+/// Each workday is first split by the daily rules, if there are any, or by the seventh day rule
+/// when every day of the workweek was worked. The regular hours left over then count towards the
+/// weekly thresholds in the order the workdays happened, so an hour that is already overtime for
+/// the day is never counted again as weekly overtime.
+pub fn calculate_overtime_hours(summaries: &mut [EmployeeShiftSummary], policy: &OvertimePolicy) {
+    let weekly_overtime_threshold = Hours::from_hours(policy.weekly_overtime_threshold);
+    let weekly_double_time_threshold = thresholds(
+        policy.weekly_overtime_threshold,
+        policy.weekly_double_time_threshold,
+    )
+    .1;
+
+    for summary in summaries.iter_mut() {
+        let worked_every_day = summary
+            .days
+            .iter()
+            .filter(|day| {
+                day.regular_hours + day.overtime_hours + day.double_time_hours > Hours::ZERO
+            })
+            .count()
+            == 7;
+        summary.seventh_day_hours = Hours::ZERO;
+
+        let mut weekly_hours_so_far = Hours::ZERO;
+        for (day_number, day) in summary.days.iter_mut().enumerate() {
+            let hours = day.regular_hours + day.overtime_hours + day.double_time_hours;
+            let seventh_day_rule = policy
+                .seventh_day
+                .filter(|_| worked_every_day && day_number == 6);
+            let (regular_hours, daily_overtime_hours, daily_double_time_hours) =
+                match (seventh_day_rule, policy.daily) {
+                    (Some(seventh_day), _) => {
+                        summary.seventh_day_hours = hours;
+                        let (overtime_threshold, double_time_threshold) = thresholds(
+                            seventh_day.overtime_threshold,
+                            seventh_day.double_time_threshold,
+                        );
+                        split_hours(
+                            Hours::ZERO,
+                            hours,
+                            overtime_threshold,
+                            double_time_threshold,
+                        )
+                    }
+                    (None, Some(daily)) => {
+                        let (overtime_threshold, double_time_threshold) =
+                            thresholds(daily.overtime_threshold, daily.double_time_threshold);
+                        split_hours(
+                            Hours::ZERO,
+                            hours,
+                            overtime_threshold,
+                            double_time_threshold,
+                        )
+                    }
+                    (None, None) => (hours, Hours::ZERO, Hours::ZERO),
+                };
+
+            let (regular_hours, weekly_overtime_hours, weekly_double_time_hours) = split_hours(
+                weekly_hours_so_far,
+                regular_hours,
+                weekly_overtime_threshold,
+                weekly_double_time_threshold,
+            );
+            weekly_hours_so_far += regular_hours + weekly_overtime_hours + weekly_double_time_hours;
+
+            day.regular_hours = regular_hours;
+            day.overtime_hours = daily_overtime_hours + weekly_overtime_hours;
+            day.double_time_hours = daily_double_time_hours + weekly_double_time_hours;
+        }
+
+        summary.regular_hours = summary.days.iter().map(|day| day.regular_hours).sum();
+        summary.overtime_hours = summary.days.iter().map(|day| day.overtime_hours).sum();
+        summary.double_time_hours = summary.days.iter().map(|day| day.double_time_hours).sum();
+    }
+}
+
+/// Converts configured thresholds to [`Hours`]. Without a double time threshold nothing is ever
+/// double time, and a double time threshold below the overtime threshold is treated as the same.
+fn thresholds(overtime_threshold: f64, double_time_threshold: Option<f64>) -> (Hours, Hours) {
+    let overtime_threshold = Hours::from_hours(overtime_threshold);
+    let double_time_threshold = double_time_threshold
+        .map(Hours::from_hours)
+        .unwrap_or(Hours::MAX)
+        .max(overtime_threshold);
+
+    (overtime_threshold, double_time_threshold)
+}
+
+/// Splits `hours`, worked after `hours_so_far` had already been worked, into the hours below the
+/// overtime threshold, between the two thresholds and above the double time threshold
+fn split_hours(
+    hours_so_far: Hours,
+    hours: Hours,
+    overtime_threshold: Hours,
+    double_time_threshold: Hours,
+) -> (Hours, Hours, Hours) {
+    let total = hours_so_far + hours;
+    let below =
+        |threshold: Hours| (total.min(threshold) - hours_so_far.min(threshold)).max(Hours::ZERO);
+
+    let regular_hours = below(overtime_threshold);
+    let overtime_hours = below(double_time_threshold) - regular_hours;
+    let double_time_hours = hours - regular_hours - overtime_hours;
+
+    (regular_hours, overtime_hours, double_time_hours)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_hours() {
+        let split = |hours_so_far: f64, hours: f64, overtime: f64, double_time: f64| {
+            let (regular_hours, overtime_hours, double_time_hours) = split_hours(
+                Hours::from_hours(hours_so_far),
+                Hours::from_hours(hours),
+                Hours::from_hours(overtime),
+                Hours::from_hours(double_time),
+            );
+            (
+                regular_hours.as_f64(),
+                overtime_hours.as_f64(),
+                double_time_hours.as_f64(),
+            )
+        };
+
+        assert_eq!(split(0., 6., 8., 12.), (6., 0., 0.));
+        assert_eq!(split(0., 10., 8., 12.), (8., 2., 0.));
+        assert_eq!(split(0., 13., 8., 12.), (8., 4., 1.));
+        assert_eq!(split(36., 6., 40., f64::INFINITY), (4., 2., 0.));
+        assert_eq!(split(44., 6., 40., 48.), (0., 4., 2.));
+    }
+}
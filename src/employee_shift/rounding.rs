@@ -0,0 +1,184 @@
+use chrono::{DateTime, Offset, TimeDelta, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+
+use crate::employee_shift::config::RoundingPolicy;
+
+const SECONDS_PER_MINUTE: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Nearest,
+    Down,
+    Up,
+}
+
+/// Rounds a shift's start and end times according to the policy. Rounding is done on the local
+/// time in `time_zone`, so intervals line up with the local clock even in time zones whose offset
+/// isn't a whole number of hours.
+pub fn round_shift(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    policy: RoundingPolicy,
+    time_zone: Tz,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    match policy {
+        RoundingPolicy::None => (start_time, end_time),
+        RoundingPolicy::Nearest(minutes) => (
+            round_time(start_time, time_zone, minutes, Direction::Nearest),
+            round_time(end_time, time_zone, minutes, Direction::Nearest),
+        ),
+        RoundingPolicy::SevenMinuteRule => {
+            // only whole minutes count, so 7 minutes and 59 seconds past still rounds down
+            let truncate = |time: DateTime<Utc>| {
+                time - TimeDelta::seconds(time.second() as i64)
+                    - TimeDelta::nanoseconds(time.nanosecond() as i64)
+            };
+            (
+                round_time(truncate(start_time), time_zone, 15, Direction::Nearest),
+                round_time(truncate(end_time), time_zone, 15, Direction::Nearest),
+            )
+        }
+        RoundingPolicy::EmployeeFavor(minutes) => (
+            round_time(start_time, time_zone, minutes, Direction::Down),
+            round_time(end_time, time_zone, minutes, Direction::Up),
+        ),
+    }
+}
+
+/// Rounds a time to a multiple of `minutes` on the local clock. Times exactly halfway between two
+/// multiples round up. The local offset at `time` is kept, so rounding across a daylight saving
+/// time transition doesn't jump by the size of the transition.
+fn round_time(
+    time: DateTime<Utc>,
+    time_zone: Tz,
+    minutes: u32,
+    direction: Direction,
+) -> DateTime<Utc> {
+    if minutes == 0 {
+        return time;
+    }
+
+    let offset = time_zone
+        .offset_from_utc_datetime(&time.naive_utc())
+        .fix()
+        .local_minus_utc() as i64;
+    let local_seconds = time.timestamp() + offset;
+    let interval = minutes as i64 * SECONDS_PER_MINUTE;
+    let past_interval = local_seconds.rem_euclid(interval);
+    let has_fraction = time.nanosecond() > 0;
+    let rounded_down = local_seconds - past_interval;
+
+    let round_up = match direction {
+        Direction::Nearest => past_interval * 2 >= interval,
+        Direction::Down => false,
+        Direction::Up => past_interval > 0 || has_fraction,
+    };
+    let rounded = if round_up {
+        rounded_down + interval
+    } else {
+        rounded_down
+    };
+
+    DateTime::from_timestamp(rounded - offset, 0).expect("rounded time is within chrono's range")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn test_round_to_nearest_interval() {
+        let round = |time: &str, minutes: u32| {
+            round_shift(
+                utc(time),
+                utc(time),
+                RoundingPolicy::Nearest(minutes),
+                Tz::UTC,
+            )
+            .0
+        };
+
+        assert_eq!(round("2024-07-01 09:02:00", 5), utc("2024-07-01 09:00:00"));
+        assert_eq!(round("2024-07-01 09:02:30", 5), utc("2024-07-01 09:05:00"));
+        assert_eq!(round("2024-07-01 09:02:59", 6), utc("2024-07-01 09:00:00"));
+        assert_eq!(round("2024-07-01 09:03:00", 6), utc("2024-07-01 09:06:00"));
+        assert_eq!(round("2024-07-01 09:07:29", 15), utc("2024-07-01 09:00:00"));
+        assert_eq!(round("2024-07-01 23:53:00", 15), utc("2024-07-02 00:00:00"));
+    }
+
+    #[test]
+    fn test_seven_minute_rule() {
+        let round = |time: &str| {
+            round_shift(
+                utc(time),
+                utc(time),
+                RoundingPolicy::SevenMinuteRule,
+                Tz::UTC,
+            )
+            .0
+        };
+
+        assert_eq!(round("2024-07-01 09:07:00"), utc("2024-07-01 09:00:00"));
+        assert_eq!(round("2024-07-01 09:07:59"), utc("2024-07-01 09:00:00"));
+        assert_eq!(round("2024-07-01 09:08:00"), utc("2024-07-01 09:15:00"));
+        assert_eq!(round("2024-07-01 09:22:00"), utc("2024-07-01 09:15:00"));
+        assert_eq!(round("2024-07-01 09:23:00"), utc("2024-07-01 09:30:00"));
+    }
+
+    #[test]
+    fn test_round_in_employees_favor() {
+        let (start_time, end_time) = round_shift(
+            utc("2024-07-01 09:01:00"),
+            utc("2024-07-01 17:01:00"),
+            RoundingPolicy::EmployeeFavor(15),
+            Tz::UTC,
+        );
+        assert_eq!(start_time, utc("2024-07-01 09:00:00"));
+        assert_eq!(end_time, utc("2024-07-01 17:15:00"));
+
+        let (start_time, end_time) = round_shift(
+            utc("2024-07-01 09:00:00"),
+            utc("2024-07-01 17:00:00"),
+            RoundingPolicy::EmployeeFavor(15),
+            Tz::UTC,
+        );
+        assert_eq!(start_time, utc("2024-07-01 09:00:00"));
+        assert_eq!(end_time, utc("2024-07-01 17:00:00"));
+    }
+
+    #[test]
+    fn test_round_in_local_time() {
+        // India is 5 hours and 30 minutes ahead of UTC, so 03:40 UTC is 09:10 local time
+        let (start_time, _) = round_shift(
+            utc("2024-07-01 03:40:00"),
+            utc("2024-07-01 03:40:00"),
+            RoundingPolicy::Nearest(60),
+            chrono_tz::Asia::Kolkata,
+        );
+        assert_eq!(start_time, utc("2024-07-01 03:30:00"));
+    }
+
+    #[test]
+    fn test_rounding_policy_from_str() {
+        assert_eq!("none".parse(), Ok(RoundingPolicy::None));
+        assert_eq!("nearest-6".parse(), Ok(RoundingPolicy::Nearest(6)));
+        assert_eq!(
+            "employee-favor-15".parse(),
+            Ok(RoundingPolicy::EmployeeFavor(15))
+        );
+        assert_eq!(
+            "seven-minute-rule".parse(),
+            Ok(RoundingPolicy::SevenMinuteRule)
+        );
+        assert!("nearest-0".parse::<RoundingPolicy>().is_err());
+        assert!("quarter-hour".parse::<RoundingPolicy>().is_err());
+    }
+}
@@ -1,189 +1,897 @@
-use crate::employee_shift::employee;
-use employee::{EmployeeShift, EmployeeShiftSummary, RawEmployeeShift};
-use std::{collections::HashMap, io::BufReader, path::Path};
+use crate::employee_shift::{
+    breaks::{break_violations, paid_periods, unpaid_breaks},
+    compression::{decompress, without_compression_extension},
+    config::{FieldNames, InputFormat, SummaryConfig, ValidationMode, WorkWeek},
+    employee,
+    error::{ShiftError, ValidationReport},
+    hours::Hours,
+    overtime::calculate_overtime_hours,
+    rounding::round_shift,
+    timestamps::{parse_timestamp, TimestampError},
+};
+use employee::{
+    Break, BreakViolation, DailyHoursSummary, EmployeeShift, EmployeeShiftSummary, InvalidShift,
+    InvalidShiftReason, RawCsvEmployeeShift, RawEmployeeShift, RestViolation, ShiftDetail,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use anyhow::{Context, Error};
-use chrono::{DateTime, Datelike, Days, NaiveDate, Timelike, Utc};
-use chrono_tz::US::Central;
+use anyhow::{bail, Context, Error};
+use chrono::{
+    DateTime, Datelike, Days, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use serde::de::{Deserializer, SeqAccess, Visitor};
 
-pub fn summarize_shifts_from_json_file(path: &Path) -> Result<Vec<EmployeeShiftSummary>, Error> {
-    let shifts = read_shifts(path)?;
+/// Weekly summaries along with the records that were skipped while reading the shifts
+#[derive(Debug)]
+pub struct ShiftSummaries {
+    pub summaries: Vec<EmployeeShiftSummary>,
+    pub validation_report: ValidationReport,
+    /// Every shift counted towards the summaries, ordered by employee and start time
+    pub shift_details: Vec<ShiftDetail>,
+}
+
+/// Reads the shifts in a JSON, NDJSON or CSV file, or stdin when the path is `-`, and summarizes
+/// them. The format is detected from the file extension unless the config sets one.
+pub fn summarize_shifts_from_file(
+    path: &Path,
+    config: &SummaryConfig,
+) -> Result<ShiftSummaries, Error> {
+    summarize_shifts_from_files(&[path.to_path_buf()], config)
+}
+
+/// Reads the shifts in every input and summarizes them together, so overlapping shifts, duplicates
+/// and weekly totals are found across files. Each input can be a file, a directory of shift files,
+/// a glob pattern such as `exports/*.csv`, or `-` for stdin.
+pub fn summarize_shifts_from_files(
+    inputs: &[PathBuf],
+    config: &SummaryConfig,
+) -> Result<ShiftSummaries, Error> {
+    let mut shifts = vec![];
+    let mut read_validation_report = ValidationReport::default();
+    for path in expand_input_paths(inputs)? {
+        let (file_shifts, file_validation_report) =
+            read_shifts(&path, config.input_format, config)?;
+        shifts.extend(file_shifts);
+        read_validation_report
+            .errors
+            .extend(file_validation_report.errors);
+    }
+
+    let mut shift_summaries = summarize_shifts(shifts, config);
+    let validation_report = &mut shift_summaries.validation_report;
+    validation_report
+        .errors
+        .extend(read_validation_report.errors);
+    validation_report.sort();
+
+    Ok(shift_summaries)
+}
+
+/// Turns directories and glob patterns into the shift files they contain. Inputs are read in the
+/// order they're given, the files in a directory or matching a pattern in order of their names, and
+/// a file given more than once is only read once.
+fn expand_input_paths(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = vec![];
+    for input in inputs {
+        let name = input.to_string_lossy();
+        if input == Path::new("-") || input.is_file() {
+            paths.push(input.clone());
+        } else if input.is_dir() {
+            let mut files = std::fs::read_dir(input)
+                .and_then(|entries| {
+                    entries
+                        .map(|entry| entry.map(|entry| entry.path()))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .with_context(|| format!("Failed to read directory: {name}"))?;
+            files.retain(|path| path.is_file() && is_shift_file(path));
+            if files.is_empty() {
+                bail!("No JSON, NDJSON or CSV files in directory: {name}");
+            }
+            files.sort();
+            paths.extend(files);
+        } else if name.contains(['*', '?', '[']) {
+            let mut files = glob::glob(&name)
+                .with_context(|| format!("Invalid glob pattern: {name}"))?
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| format!("Failed to read files matching: {name}"))?;
+            files.retain(|path| path.is_file());
+            if files.is_empty() {
+                bail!("No files match: {name}");
+            }
+            files.sort();
+            paths.extend(files);
+        } else {
+            // missing files are reported when they're opened
+            paths.push(input.clone());
+        }
+    }
+
+    let mut seen = HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
 
-    let summaries = summarize_all_employee_hours(shifts);
+    Ok(paths)
+}
+
+/// Whether a file found in a directory of inputs is one shifts are read from, compressed or not
+fn is_shift_file(path: &Path) -> bool {
+    without_compression_extension(path)
+        .extension()
+        .is_some_and(|extension| {
+            matches!(
+                extension.to_string_lossy().to_ascii_lowercase().as_str(),
+                "json" | "ndjson" | "jsonl" | "csv"
+            )
+        })
+}
+
+/// Summarizes shifts that have already been read into weekly summaries for each employee
+pub fn summarize_shifts(shifts: Vec<EmployeeShift>, config: &SummaryConfig) -> ShiftSummaries {
+    let mut validation_report = ValidationReport::default();
+    let mut shift_details = Vec::with_capacity(shifts.len());
+    let summaries =
+        summarize_all_employee_hours(shifts, config, &mut validation_report, &mut shift_details);
+    validation_report.sort();
+    shift_details.sort_by_key(|detail| (detail.employee_id, detail.start_time, detail.shift_id));
     let mut summaries: Vec<EmployeeShiftSummary> = summaries.into_values().collect();
-    calculate_overtime_hours(&mut summaries);
+    calculate_overtime_hours(&mut summaries, &config.overtime_policy);
+
+    ShiftSummaries {
+        summaries,
+        validation_report,
+        shift_details,
+    }
+}
+
+/// A record's position in its file, starting from 1, and the record parsed from it
+type RawRecord = (usize, Result<RawEmployeeShift, ShiftError>);
+
+/// Reads the shifts in a file, or stdin when the path is `-`. Without an `input_format`, a file's
+/// format comes from its extension and stdin's from its first character.
+fn read_shifts(
+    path: &Path,
+    input_format: Option<InputFormat>,
+    config: &SummaryConfig,
+) -> Result<(Vec<EmployeeShift>, ValidationReport), Error> {
+    let source = input_name(path);
+    let file: Arc<str> = Arc::from(source.as_str());
+    let reader: Box<dyn BufRead> = if path == Path::new("-") {
+        Box::new(std::io::stdin().lock())
+    } else {
+        let file =
+            std::fs::File::open(path).with_context(|| format!("Failed to open file: {source}"))?;
+        Box::new(BufReader::new(file))
+    };
+    let mut reader =
+        decompress(reader).with_context(|| format!("Failed to read file: {source}"))?;
+    let input_format = match input_format {
+        Some(input_format) => input_format,
+        None if path == Path::new("-") => InputFormat::detect(&mut reader)
+            .with_context(|| format!("Failed to read file: {source}"))?,
+        None => InputFormat::from_path(path),
+    };
+
+    // CSV and NDJSON are read one record at a time, so only the shifts themselves are kept in
+    // memory rather than the whole input
+    let records: Box<dyn Iterator<Item = Result<RawRecord, Error>>> = match input_format {
+        InputFormat::Json => Box::new(
+            read_json_records(reader, &file, &config.field_names)?
+                .into_iter()
+                .map(Ok),
+        ),
+        InputFormat::Ndjson => Box::new(read_ndjson_records(
+            reader,
+            file.clone(),
+            config.field_names.clone(),
+        )),
+        InputFormat::Csv => Box::new(
+            read_csv_records(reader, file.clone(), &config.field_names)
+                .with_context(|| format!("Failed to read file: {source}"))?
+                .map(Ok),
+        ),
+    };
+
+    let mut shifts = vec![];
+    let mut validation_report = ValidationReport::default();
+    for record in records {
+        let (record_number, record) =
+            record.with_context(|| format!("Failed to read file: {source}"))?;
+        let shift = record.and_then(|shift| validate_shift(&shift, &file, record_number, config));
+
+        match (shift, config.validation_mode) {
+            (Ok(shift), _) => shifts.push(shift),
+            (Err(err), ValidationMode::Strict) => {
+                return Err(err).with_context(|| format!("Invalid shift in file: {source}"))
+            }
+            (Err(err), ValidationMode::Report) => validation_report.errors.push(err),
+        }
+    }
+
+    Ok((shifts, validation_report))
+}
+
+/// Name of the input for error messages. A path of `-` means stdin.
+fn input_name(path: &Path) -> String {
+    if path == Path::new("-") {
+        "stdin".to_string()
+    } else {
+        path.to_string_lossy().into_owned()
+    }
+}
+
+/// Reads a JSON array of shifts. The array is streamed so only one record is held as a JSON value
+/// at a time, and records are parsed one at a time so a single bad record doesn't stop the others
+/// from being read.
+fn read_json_records(
+    reader: impl Read,
+    file: &Arc<str>,
+    field_names: &FieldNames,
+) -> Result<Vec<RawRecord>, Error> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let records = deserializer
+        .deserialize_seq(JsonRecordsVisitor { file, field_names })
+        .and_then(|records| deserializer.end().map(|_| records))
+        .with_context(|| {
+            "serde library has a bug when reporting the correct line number where error occurred. Reported error line will be wrong but the actual error will probably be on a nearby line"
+        })?;
+
+    Ok(records)
+}
+
+/// Parses the elements of a JSON array into shifts as they're read
+struct JsonRecordsVisitor<'a> {
+    file: &'a Arc<str>,
+    field_names: &'a FieldNames,
+}
+
+impl<'de> Visitor<'de> for JsonRecordsVisitor<'_> {
+    type Value = Vec<RawRecord>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of shifts")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut records = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(mut record) = seq.next_element::<serde_json::Value>()? {
+            let record_number = records.len() + 1;
+            rename_fields(&mut record, self.field_names);
+            let shift = serde_json::from_value::<RawEmployeeShift>(record).map_err(|err| {
+                ShiftError::Parse {
+                    file: self.file.clone(),
+                    record: record_number,
+                    message: err.to_string(),
+                }
+            });
+            records.push((record_number, shift));
+        }
+        Ok(records)
+    }
+}
+
+/// Reads newline delimited JSON, one shift object per line, as the lines come in. The record
+/// number of a shift is its line number, and blank lines are skipped.
+fn read_ndjson_records(
+    reader: impl BufRead,
+    file: Arc<str>,
+    field_names: FieldNames,
+) -> impl Iterator<Item = Result<RawRecord, Error>> {
+    // lines are split as bytes rather than strings, so a line that isn't valid UTF-8 is reported
+    // as a bad record by the JSON parser instead of stopping the whole read
+    reader
+        .split(b'\n')
+        .enumerate()
+        .filter_map(move |(index, line)| {
+            let record_number = index + 1;
+            match line {
+                Err(err) => Some(Err(Error::from(err))),
+                Ok(line) if line.iter().all(u8::is_ascii_whitespace) => None,
+                Ok(line) => {
+                    let shift =
+                        parse_json_line(&line, &field_names).map_err(|err| ShiftError::Parse {
+                            file: file.clone(),
+                            record: record_number,
+                            message: err.to_string(),
+                        });
+                    Some(Ok((record_number, shift)))
+                }
+            }
+        })
+}
 
-    Ok(summaries)
+/// Parses a line of NDJSON, only going through a JSON value first when fields need renaming
+fn parse_json_line(
+    line: &[u8],
+    field_names: &FieldNames,
+) -> Result<RawEmployeeShift, serde_json::Error> {
+    if field_names.is_empty() {
+        return serde_json::from_slice(line);
+    }
+    let mut record = serde_json::from_slice(line)?;
+    rename_fields(&mut record, field_names);
+    serde_json::from_value(record)
 }
 
-fn read_shifts(path: &Path) -> Result<Vec<EmployeeShift>, Error> {
-    let file = std::fs::File::open(path)
-        .with_context(|| format!("Failed to open file: {}", path.to_string_lossy()))?;
+/// Renames the keys of a JSON record the input names differently to the usual field names
+fn rename_fields(record: &mut serde_json::Value, field_names: &FieldNames) {
+    let Some(record) = record.as_object_mut() else {
+        return;
+    };
+    // every renamed value is taken out before any are put back, so names can be swapped
+    let renamed: Vec<_> = field_names
+        .renames()
+        .filter_map(|(name, field)| record.remove(name).map(|value| (field, value)))
+        .collect();
+    for (field, value) in renamed {
+        record.insert(field.to_string(), value);
+    }
+}
 
-    let reader = BufReader::new(file);
-    let raw_shifts: Vec<RawEmployeeShift> = serde_json::from_reader(reader).with_context(|| {
-        "serde library has a bug when reporting the correct line number where error occurred. Reported error line will be wrong but the actual error will probably be on a nearby line"
-    })?;
+/// Reads CSV shifts with a header row naming the columns. Each row after the header is a record,
+/// so the first row of shifts is record 1.
+fn read_csv_records(
+    reader: impl Read,
+    file: Arc<str>,
+    field_names: &FieldNames,
+) -> Result<impl Iterator<Item = RawRecord>, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = reader
+        .headers()?
+        .iter()
+        .map(|header| field_names.field_name(header))
+        .collect();
+    reader.set_headers(headers);
 
-    Ok(raw_shifts
-        .into_iter()
-        .map(|shift| validate_shift(&shift).unwrap())
-        .collect())
+    Ok(reader
+        .into_deserialize::<RawCsvEmployeeShift>()
+        .enumerate()
+        .map(move |(index, record)| {
+            let record_number = index + 1;
+            let shift = record
+                .map(RawEmployeeShift::from)
+                .map_err(|err| ShiftError::Parse {
+                    file: file.clone(),
+                    record: record_number,
+                    message: err.to_string(),
+                });
+            (record_number, shift)
+        }))
 }
 
-// would add validation to check end time is after start time
-fn validate_shift(shift: &RawEmployeeShift) -> Result<EmployeeShift, Error> {
+fn validate_shift(
+    shift: &RawEmployeeShift,
+    file: &Arc<str>,
+    record: usize,
+    config: &SummaryConfig,
+) -> Result<EmployeeShift, ShiftError> {
+    let shift_time_zone = shift
+        .time_zone
+        .as_deref()
+        .map(|time_zone| {
+            time_zone
+                .parse::<Tz>()
+                .map_err(|_| ShiftError::BadTimeZone {
+                    file: file.clone(),
+                    record,
+                    shift_id: shift.shift_id,
+                    value: time_zone.to_string(),
+                })
+        })
+        .transpose()?;
+    // timestamps without an offset are local times in the zone the shift is summarized in
+    let time_zone = config.time_zone_for_employee(shift.employee_id, shift_time_zone);
+
+    let parse_time = |field: &'static str, value: &str| {
+        parse_timestamp(
+            value,
+            &config.timestamp_format,
+            time_zone,
+            config.ambiguous_times,
+        )
+        .map_err(|err| match err {
+            TimestampError::Invalid => ShiftError::BadTimestamp {
+                file: file.clone(),
+                record,
+                shift_id: shift.shift_id,
+                field,
+                value: value.to_string(),
+                format: config.timestamp_format.clone(),
+            },
+            TimestampError::Ambiguous => ShiftError::AmbiguousTimestamp {
+                file: file.clone(),
+                record,
+                shift_id: shift.shift_id,
+                field,
+                value: value.to_string(),
+                time_zone,
+            },
+            TimestampError::Nonexistent => ShiftError::NonexistentTimestamp {
+                file: file.clone(),
+                record,
+                shift_id: shift.shift_id,
+                field,
+                value: value.to_string(),
+                time_zone,
+            },
+        })
+    };
+
+    let start_time = parse_time("StartTime", &shift.start_time)?;
+    let end_time = parse_time("EndTime", &shift.end_time)?;
+
+    let mut breaks = Vec::with_capacity(shift.breaks.len());
+    for (index, raw_break) in shift.breaks.iter().enumerate() {
+        let shift_break = Break {
+            start_time: parse_time("Breaks.StartTime", &raw_break.start_time)?,
+            end_time: parse_time("Breaks.EndTime", &raw_break.end_time)?,
+        };
+        if shift_break.end_time <= shift_break.start_time
+            || shift_break.start_time < start_time
+            || shift_break.end_time > end_time
+        {
+            return Err(ShiftError::BadBreak {
+                file: file.clone(),
+                record,
+                shift_id: shift.shift_id,
+                break_number: index + 1,
+            });
+        }
+        breaks.push(shift_break);
+    }
+
     Ok(EmployeeShift {
+        file: file.clone(),
+        record,
         shift_id: shift.shift_id,
         employee_id: shift.employee_id,
-        start_time: DateTime::parse_from_rfc3339(&shift.start_time)
-            .with_context(|| format!("start time was not rfc3339 compliant: {:?}", shift))?
-            .into(),
-        end_time: DateTime::parse_from_rfc3339(&shift.end_time)
-            .with_context(|| format!("end time was not rfc3339 compliant: {:?}", shift))?
-            .into(),
+        start_time,
+        end_time,
+        time_zone: shift_time_zone,
+        breaks,
     })
 }
 
-fn calculate_overtime_hours(summaries: &mut [EmployeeShiftSummary]) {
-    for summary in summaries.iter_mut() {
-        if summary.regular_hours > 40. {
-            summary.overtime_hours = summary.regular_hours - 40.;
-            summary.regular_hours = 40.;
-        }
+/// Checks that a shift ends after it starts and isn't implausibly long
+fn validate_shift_length(shift: &EmployeeShift, max_hours: Option<f64>) -> Result<(), ShiftError> {
+    let hours = Hours::from_duration(shift.end_time - shift.start_time).as_f64();
+    if shift.end_time < shift.start_time {
+        return Err(ShiftError::EndBeforeStart {
+            file: shift.file.clone(),
+            record: shift.record,
+            shift_id: shift.shift_id,
+        });
+    }
+    if shift.end_time == shift.start_time {
+        return Err(ShiftError::ZeroLength {
+            file: shift.file.clone(),
+            record: shift.record,
+            shift_id: shift.shift_id,
+        });
+    }
+    match max_hours {
+        Some(max_hours) if hours > max_hours => Err(ShiftError::TooLong {
+            file: shift.file.clone(),
+            record: shift.record,
+            shift_id: shift.shift_id,
+            hours,
+            max_hours,
+        }),
+        _ => Ok(()),
     }
 }
 
 fn summarize_all_employee_hours(
     shifts: Vec<EmployeeShift>,
+    config: &SummaryConfig,
+    validation_report: &mut ValidationReport,
+    shift_details: &mut Vec<ShiftDetail>,
 ) -> HashMap<(u64, NaiveDate), EmployeeShiftSummary> {
-    let shifts = {
-        let mut map = HashMap::new();
-        for shift in shifts {
-            map.insert((shift.employee_id, shift.shift_id, shift.start_time), shift);
+    let mut summaries: HashMap<(u64, NaiveDate), EmployeeShiftSummary> = HashMap::new();
+
+    let (shifts, conflicting_duplicates) = remove_duplicate_shifts(shifts, validation_report);
+    for shift in conflicting_duplicates {
+        let invalid_shift = InvalidShift::new(shift.shift_id, InvalidShiftReason::DuplicateShiftID);
+        add_invalid_shift_to_summary(&mut summaries, &shift, invalid_shift, config);
+    }
+
+    let mut valid_length_shifts = Vec::with_capacity(shifts.len());
+    for shift in shifts {
+        match validate_shift_length(&shift, config.max_shift_hours) {
+            Ok(()) => valid_length_shifts.push(shift),
+            Err(err) => {
+                let invalid_shift = InvalidShift::new(
+                    shift.shift_id,
+                    err.invalid_shift_reason()
+                        .expect("shift length errors are always invalid shift reasons"),
+                );
+                add_invalid_shift_to_summary(&mut summaries, &shift, invalid_shift, config);
+                validation_report.errors.push(err);
+            }
         }
-        map
-    };
+    }
 
-    let mut summaries: HashMap<(u64, NaiveDate), EmployeeShiftSummary> = HashMap::new();
-    for shift in shifts.values() {
-        // need the start of week for the shift end time because it's possible that a shift crosses
-        // into the next week, which means the start of the week for the end of the shift is in
-        // the next week and is necessary for hours worked calculations
-        let (
-            start_of_week_for_start_time,
-            end_of_week_for_start_time,
-            start_of_week_for_end_time,
-            start_of_week_date_for_start_time,
-            start_of_week_date_for_end_time,
-        ) = get_start_of_week_for_shift(shift.start_time, shift.end_time);
-
-        if does_shift_overlap_with_another_for_employee(shift, &shifts) {
-            summaries
-                .entry((shift.employee_id, start_of_week_date_for_start_time))
-                .and_modify(|summary| summary.invalid_shifts.push(shift.shift_id))
-                .or_insert(EmployeeShiftSummary {
-                    employee_id: shift.employee_id,
-                    start_of_week: start_of_week_date_for_start_time.to_string(),
-                    regular_hours: 0.,
-                    overtime_hours: 0.,
-                    invalid_shifts: vec![shift.shift_id],
-                });
-            continue;
+    for employee_shifts in group_shifts_by_employee(valid_length_shifts).values() {
+        let overlapping_shift_ids = find_overlapping_shifts(employee_shifts);
+        let mut counted_shifts = Vec::with_capacity(employee_shifts.len());
+        for (shift, overlapping_shift_ids) in employee_shifts.iter().zip(overlapping_shift_ids) {
+            if !overlapping_shift_ids.is_empty() {
+                let invalid_shift = InvalidShift {
+                    shift_id: shift.shift_id,
+                    reason: InvalidShiftReason::Overlapping,
+                    conflicting_shift_ids: overlapping_shift_ids,
+                };
+                add_invalid_shift_to_summary(&mut summaries, shift, invalid_shift, config);
+                continue;
+            }
+
+            shift_details.push(add_shift_to_summaries(&mut summaries, shift, config));
+            counted_shifts.push(shift);
         }
 
-        if start_of_week_for_start_time != start_of_week_for_end_time {
-            let hours_first_week = end_of_week_for_start_time - shift.start_time;
-            let hours_next_week = shift.end_time - start_of_week_for_end_time;
+        if let Some(minimum_rest_hours) = config.minimum_rest_hours {
+            let minimum_rest = Hours::from_hours(minimum_rest_hours);
+            for (shift, rest_violation) in find_rest_violations(&counted_shifts, minimum_rest) {
+                // reported in the week the later shift starts in
+                let (_, _, start_of_week_date) = get_week_containing(
+                    shift.start_time,
+                    config.time_zone_for_shift(shift),
+                    &config.work_week,
+                );
+                let summary = summaries
+                    .entry((shift.employee_id, start_of_week_date))
+                    .or_insert_with(|| {
+                        EmployeeShiftSummary::new(shift.employee_id, start_of_week_date)
+                    });
+                summary.rest_premium_hours += rest_violation.premium_hours;
+                summary.rest_violations.push(rest_violation);
+            }
+        }
+    }
+    summaries
+}
 
-            // add hours for the week start time is part of
-            summaries
-                .entry((shift.employee_id, start_of_week_date_for_start_time))
-                .and_modify(|summary| {
-                    summary.regular_hours += hours_first_week.num_minutes() as f64 / 60.
-                })
-                .or_insert(EmployeeShiftSummary {
-                    employee_id: shift.employee_id,
-                    start_of_week: start_of_week_date_for_start_time.to_string(),
-                    regular_hours: hours_first_week.num_minutes() as f64 / 60.,
-                    overtime_hours: 0.,
-                    invalid_shifts: vec![],
-                });
+/// Separates shifts that share a shift id with another record from the rest.
+///
+/// Records that are exact copies of an earlier record with the same shift id are dropped with a
+/// warning. When records share a shift id but have different data there's no telling which one is
+/// right, so all of them are returned as conflicting duplicates instead.
+fn remove_duplicate_shifts(
+    shifts: Vec<EmployeeShift>,
+    validation_report: &mut ValidationReport,
+) -> (Vec<EmployeeShift>, Vec<EmployeeShift>) {
+    let mut shifts_by_id: HashMap<u64, Vec<EmployeeShift>> = HashMap::new();
+    for shift in shifts {
+        shifts_by_id.entry(shift.shift_id).or_default().push(shift);
+    }
 
-            // add hours for the week end time is part of
-            summaries
-                .entry((shift.employee_id, start_of_week_date_for_end_time))
-                .and_modify(|summary| {
-                    summary.regular_hours += hours_next_week.num_minutes() as f64 / 60.
-                })
-                .or_insert(EmployeeShiftSummary {
-                    employee_id: shift.employee_id,
-                    start_of_week: start_of_week_date_for_end_time.to_string(),
-                    regular_hours: hours_next_week.num_minutes() as f64 / 60.,
-                    overtime_hours: 0.,
-                    invalid_shifts: vec![],
-                });
+    let mut unique_shifts = Vec::with_capacity(shifts_by_id.len());
+    let mut conflicting_duplicates = vec![];
+    for (_, mut records) in shifts_by_id {
+        // records are kept in the order they were read, so the first is the earliest in the input
+        let first = &records[0];
+        let first_record = first.record;
+
+        if records.iter().all(|shift| shift.has_same_data(first)) {
+            validation_report
+                .warnings
+                .extend(
+                    records[1..]
+                        .iter()
+                        .map(|shift| ShiftError::DuplicateRecord {
+                            file: shift.file.clone(),
+                            record: shift.record,
+                            shift_id: shift.shift_id,
+                            first_file: first.file.clone(),
+                            first_record,
+                        }),
+                );
+            unique_shifts.push(records.swap_remove(0));
         } else {
-            let hours = shift.end_time - shift.start_time;
+            validation_report
+                .errors
+                .extend(
+                    records[1..]
+                        .iter()
+                        .map(|shift| ShiftError::ConflictingDuplicate {
+                            file: shift.file.clone(),
+                            record: shift.record,
+                            shift_id: shift.shift_id,
+                            first_file: first.file.clone(),
+                            first_record,
+                        }),
+                );
+            conflicting_duplicates.extend(records);
+        }
+    }
+
+    (unique_shifts, conflicting_duplicates)
+}
+
+/// Rounds the shift's start and end times and adds the paid hours between them, leaving out unpaid
+/// breaks, to the summaries of the weeks it was worked in. Break violations go in the summary of
+/// the week the shift starts in.
+fn add_shift_to_summaries(
+    summaries: &mut HashMap<(u64, NaiveDate), EmployeeShiftSummary>,
+    shift: &EmployeeShift,
+    config: &SummaryConfig,
+) -> ShiftDetail {
+    let time_zone = config.time_zone_for_shift(shift);
+    let (start_time, end_time) =
+        round_shift(shift.start_time, shift.end_time, config.rounding, time_zone);
+    let breaks = unpaid_breaks(shift, start_time, end_time, config.automatic_break);
+
+    let mut hours = Hours::ZERO;
+    for (period_start, period_end) in paid_periods(start_time, end_time, &breaks) {
+        add_period_to_summaries(
+            summaries,
+            shift.employee_id,
+            period_start,
+            period_end,
+            time_zone,
+            &config.work_week,
+        );
+        hours += Hours::from_duration(period_end - period_start);
+    }
 
+    if let Some(break_rules) = &config.break_rules {
+        // an automatic break is only deducted from the hours, it isn't a break the employee
+        // actually took, so only the recorded breaks count towards the rules
+        let violations = break_violations(
+            &paid_periods(start_time, end_time, &shift.breaks),
+            break_rules,
+        );
+        if !violations.is_empty() {
+            // violations belong to the whole shift rather than to any one break, so they're
+            // reported in the week the shift starts in, like invalid shifts, even when the shift
+            // or its breaks carry on into the next week
+            let (_, _, start_of_week_date) =
+                get_week_containing(start_time, time_zone, &config.work_week);
             summaries
-                .entry((shift.employee_id, start_of_week_date_for_start_time))
-                .and_modify(|summary| summary.regular_hours += hours.num_minutes() as f64 / 60.)
-                .or_insert(EmployeeShiftSummary {
-                    employee_id: shift.employee_id,
-                    start_of_week: start_of_week_date_for_start_time.to_string(),
-                    regular_hours: hours.num_minutes() as f64 / 60.,
-                    overtime_hours: 0.,
-                    invalid_shifts: vec![],
-                });
+                .entry((shift.employee_id, start_of_week_date))
+                .or_insert_with(|| EmployeeShiftSummary::new(shift.employee_id, start_of_week_date))
+                .break_violations
+                .extend(violations.into_iter().map(|violation| BreakViolation {
+                    shift_id: shift.shift_id,
+                    violation,
+                }));
         }
     }
+
+    ShiftDetail {
+        shift_id: shift.shift_id,
+        employee_id: shift.employee_id,
+        time_zone,
+        start_time: shift.start_time,
+        end_time: shift.end_time,
+        rounded_start_time: start_time,
+        rounded_end_time: end_time,
+        hours,
+        unpaid_break_hours: Hours::from_duration(end_time - start_time) - hours,
+    }
+}
+
+/// Adds the hours worked between `start_time` and `end_time` to the summaries of the weeks they
+/// were worked in
+fn add_period_to_summaries(
+    summaries: &mut HashMap<(u64, NaiveDate), EmployeeShiftSummary>,
+    employee_id: u64,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    time_zone: Tz,
+    work_week: &WorkWeek,
+) {
+    // need the start of week for the shift end time because it's possible that a shift crosses
+    // into the next week, which means the start of the week for the end of the shift is in
+    // the next week and is necessary for hours worked calculations
+    let (
+        start_of_week_for_start_time,
+        end_of_week_for_start_time,
+        start_of_week_for_end_time,
+        start_of_week_date_for_start_time,
+        _,
+    ) = get_start_of_week_for_shift(start_time, end_time, time_zone, work_week);
+
+    // add hours for the week start time is part of. A shift ending right as the next week starts
+    // has no hours in the next week
+    let crosses_into_next_week = start_of_week_for_start_time != start_of_week_for_end_time
+        && end_time > end_of_week_for_start_time;
+    add_hours_to_summary(
+        summaries
+            .entry((employee_id, start_of_week_date_for_start_time))
+            .or_insert_with(|| {
+                EmployeeShiftSummary::new(employee_id, start_of_week_date_for_start_time)
+            }),
+        start_time,
+        if crosses_into_next_week {
+            end_of_week_for_start_time
+        } else {
+            end_time
+        },
+        time_zone,
+        work_week,
+    );
+    if !crosses_into_next_week {
+        return;
+    }
+
+    // add hours for every week after it, which is more than one when the period is longer than a
+    // week. Each week starts where the one before it ended, since a week starting in an hour that
+    // repeats when clocks fall back can have its start resolved to either occurrence depending on
+    // the time it's found from
+    let mut segment_start = end_of_week_for_start_time;
+    while segment_start < end_time {
+        let (_, end_of_week, start_of_week_date) =
+            get_week_containing(segment_start, time_zone, work_week);
+        let segment_end = end_of_week.min(end_time);
+        add_hours_to_summary(
+            summaries
+                .entry((employee_id, start_of_week_date))
+                .or_insert_with(|| EmployeeShiftSummary::new(employee_id, start_of_week_date)),
+            segment_start,
+            segment_end,
+            time_zone,
+            work_week,
+        );
+
+        segment_start = segment_end;
+    }
+}
+
+/// Lists an invalid shift in the summary of the week the shift starts in
+fn add_invalid_shift_to_summary(
+    summaries: &mut HashMap<(u64, NaiveDate), EmployeeShiftSummary>,
+    shift: &EmployeeShift,
+    invalid_shift: InvalidShift,
+    config: &SummaryConfig,
+) {
+    let (_, _, start_of_week_date) = get_week_containing(
+        shift.start_time,
+        config.time_zone_for_shift(shift),
+        &config.work_week,
+    );
+
     summaries
+        .entry((shift.employee_id, start_of_week_date))
+        .or_insert_with(|| EmployeeShiftSummary::new(shift.employee_id, start_of_week_date))
+        .invalid_shifts
+        .push(invalid_shift);
 }
 
-fn does_shift_overlap_with_another_for_employee(
-    current_shift: &EmployeeShift,
-    shifts: &HashMap<(u64, u64, DateTime<Utc>), EmployeeShift>,
-) -> bool {
-    for (_, other_shift) in shifts
-        .iter()
-        .filter(|(key, _)| key.0 == current_shift.employee_id)
-    {
-        if current_shift.shift_id != other_shift.shift_id {
-            // check if current shift overlaps with other shift
-            if current_shift.start_time > other_shift.start_time
-                && current_shift.start_time < other_shift.end_time
-            {
-                return true;
-            }
-            if current_shift.end_time > other_shift.start_time
-                && current_shift.end_time < other_shift.end_time
-            {
-                return true;
-            }
+/// Adds the hours worked between `start_time` and `end_time` to the week's summary and to each of
+/// the workdays they fall on. Both times must be within the summary's week.
+fn add_hours_to_summary(
+    summary: &mut EmployeeShiftSummary,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    time_zone: Tz,
+    work_week: &WorkWeek,
+) {
+    summary.regular_hours += Hours::from_duration(end_time - start_time);
 
-            // check if other shift overlaps with current shift
-            // two different checks are necessary in case a shift completely
-            // encompasses the other shift
-            if other_shift.start_time > current_shift.start_time
-                && other_shift.start_time < current_shift.end_time
-            {
-                return true;
+    let mut segment_start = start_time;
+    while segment_start < end_time {
+        let (_, end_of_workday, workday_date) =
+            get_workday_containing(segment_start, time_zone, work_week.start_time);
+        let segment_end = end_of_workday.min(end_time);
+        let hours = Hours::from_duration(segment_end - segment_start);
+
+        let workday_date = workday_date.to_string();
+        match summary.days.iter_mut().find(|day| day.date == workday_date) {
+            Some(day) => day.regular_hours += hours,
+            None => {
+                summary.days.push(DailyHoursSummary {
+                    date: workday_date,
+                    regular_hours: hours,
+                    overtime_hours: Hours::ZERO,
+                    double_time_hours: Hours::ZERO,
+                });
+                summary.days.sort_by(|a, b| a.date.cmp(&b.date));
             }
-            if other_shift.end_time > current_shift.start_time
-                && other_shift.end_time < current_shift.end_time
-            {
-                return true;
+        }
+
+        segment_start = segment_end;
+    }
+}
+
+/// Groups shifts by employee, with each employee's shifts sorted by start time
+fn group_shifts_by_employee(shifts: Vec<EmployeeShift>) -> HashMap<u64, Vec<EmployeeShift>> {
+    let mut shifts_by_employee: HashMap<u64, Vec<EmployeeShift>> = HashMap::new();
+    for shift in shifts {
+        shifts_by_employee
+            .entry(shift.employee_id)
+            .or_default()
+            .push(shift);
+    }
+
+    for shifts in shifts_by_employee.values_mut() {
+        shifts.sort_by_key(|shift| (shift.start_time, shift.end_time, shift.shift_id));
+    }
+
+    shifts_by_employee
+}
+
+/// Finds consecutive shifts with less than `minimum_rest` between the end of one and the start of
+/// the next, returning the later shift of each pair along with the violation.
+///
+/// `shifts` must be a single employee's shifts sorted by start time with no overlaps. The premium
+/// hours are the part of the later shift worked before the minimum rest would have ended.
+fn find_rest_violations<'a>(
+    shifts: &[&'a EmployeeShift],
+    minimum_rest: Hours,
+) -> Vec<(&'a EmployeeShift, RestViolation)> {
+    shifts
+        .windows(2)
+        .filter_map(|pair| {
+            let (previous_shift, shift) = (pair[0], pair[1]);
+            let rest_hours = Hours::from_duration(shift.start_time - previous_shift.end_time);
+            if rest_hours >= minimum_rest {
+                return None;
             }
+
+            // rest too long to add to a time never ends, so the whole shift is worked during it
+            let end_of_rest = TimeDelta::try_seconds(minimum_rest.seconds())
+                .and_then(|rest| previous_shift.end_time.checked_add_signed(rest))
+                .unwrap_or(shift.end_time);
+            let premium_hours =
+                Hours::from_duration(end_of_rest.min(shift.end_time) - shift.start_time);
+            Some((
+                shift,
+                RestViolation {
+                    shift_id: shift.shift_id,
+                    previous_shift_id: previous_shift.shift_id,
+                    rest_hours,
+                    premium_hours,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Returns the ids of the other shifts each shift overlaps with, in the same order as `shifts`.
+///
+/// `shifts` must be a single employee's shifts sorted by start time. Shifts are swept in order
+/// while keeping track of the earlier shifts that haven't ended yet. Every one of those overlaps
+/// the next shift, since they started no later than it did, so this takes linear time on top of
+/// the sort apart from the time spent listing the overlaps themselves. A shift that ends exactly
+/// when another starts doesn't overlap it.
+fn find_overlapping_shifts(shifts: &[EmployeeShift]) -> Vec<Vec<u64>> {
+    let mut overlapping_shift_ids = vec![vec![]; shifts.len()];
+    let mut unfinished_shifts: Vec<usize> = vec![];
+
+    for (current, current_shift) in shifts.iter().enumerate() {
+        unfinished_shifts.retain(|&other| shifts[other].end_time > current_shift.start_time);
+
+        for &other in &unfinished_shifts {
+            let other_shift_id = shifts[other].shift_id;
+            overlapping_shift_ids[current].push(other_shift_id);
+            overlapping_shift_ids[other].push(current_shift.shift_id);
         }
+
+        unfinished_shifts.push(current);
+    }
+
+    for shift_ids in overlapping_shift_ids.iter_mut() {
+        shift_ids.sort();
     }
 
-    false
+    overlapping_shift_ids
 }
 
 fn get_start_of_week_for_shift(
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
+    time_zone: Tz,
+    work_week: &WorkWeek,
 ) -> (
     DateTime<Utc>,
     DateTime<Utc>,
@@ -191,71 +899,151 @@ fn get_start_of_week_for_shift(
     NaiveDate,
     NaiveDate,
 ) {
-    // convert to central time before calculating sunday midnight date
-    let start_time_central = start_time.with_timezone(&Central);
-    let start_work_week =
-        start_time_central - Days::new(start_time_central.weekday().num_days_from_sunday() as u64);
-    let start_of_week_for_start_time = start_work_week
-        .with_hour(0)
-        .unwrap()
-        .with_minute(0)
-        .unwrap()
-        .with_second(0)
-        .unwrap();
-
-    let end_of_work_week_for_start_time = start_of_week_for_start_time + Days::new(7);
-
-    let start_of_week_for_end_time = {
-        // convert to central time before calculating sunday midnight date
-        let end_time_central = end_time.with_timezone(&Central);
-        let start_work_week =
-            end_time_central - Days::new(end_time_central.weekday().num_days_from_sunday() as u64);
-        start_work_week
-            .with_hour(0)
-            .unwrap()
-            .with_minute(0)
-            .unwrap()
-            .with_second(0)
-            .unwrap()
-    };
-
-    let start_of_week_date_for_start_time = NaiveDate::from_ymd_opt(
-        start_of_week_for_start_time.year(),
-        start_of_week_for_start_time.month(),
-        start_of_week_for_start_time.day(),
-    )
-    .unwrap();
-
-    let start_of_week_date_for_end_time = NaiveDate::from_ymd_opt(
-        start_of_week_for_end_time.year(),
-        start_of_week_for_end_time.month(),
-        start_of_week_for_end_time.day(),
-    )
-    .unwrap();
+    let (
+        start_of_week_for_start_time,
+        end_of_work_week_for_start_time,
+        start_of_week_date_for_start_time,
+    ) = get_week_containing(start_time, time_zone, work_week);
+    let (start_of_week_for_end_time, _, start_of_week_date_for_end_time) =
+        get_week_containing(end_time, time_zone, work_week);
 
     (
-        start_of_week_for_start_time.to_utc(),
-        end_of_work_week_for_start_time.to_utc(),
-        start_of_week_for_end_time.to_utc(),
+        start_of_week_for_start_time,
+        end_of_work_week_for_start_time,
+        start_of_week_for_end_time,
         start_of_week_date_for_start_time,
         start_of_week_date_for_end_time,
     )
 }
 
+/// Returns the start and end of the week containing `time` along with the local date the week
+/// starts on. Week boundaries are the local start of the workweek on either side of `time`, so a
+/// week containing a daylight saving time transition is 167 or 169 hours long rather than 168.
+fn get_week_containing(
+    time: DateTime<Utc>,
+    time_zone: Tz,
+    work_week: &WorkWeek,
+) -> (DateTime<Utc>, DateTime<Utc>, NaiveDate) {
+    // work with wall clock times in the business's local time before calculating the start of the
+    // workweek so the week's start never lands on a local time that doesn't exist or happens twice
+    let local_time = time.with_timezone(&time_zone).naive_local();
+    let days_since_start_day = (local_time.weekday().num_days_from_sunday() + 7
+        - work_week.start_day.num_days_from_sunday())
+        % 7;
+    let mut start_of_week_date = local_time.date() - Days::new(days_since_start_day as u64);
+    // on the start day itself, times before the start time still belong to the previous week
+    if local_time < start_of_week_date.and_time(work_week.start_time) {
+        start_of_week_date = start_of_week_date - Days::new(7);
+    }
+    let end_of_week_date = start_of_week_date + Days::new(7);
+
+    let (start_of_week, end_of_week) = resolve_period_containing(
+        time,
+        start_of_week_date.and_time(work_week.start_time),
+        end_of_week_date.and_time(work_week.start_time),
+        time_zone,
+    );
+    (start_of_week, end_of_week, start_of_week_date)
+}
+
+/// Returns the start and end of the workday containing `time` along with the local date the
+/// workday starts on. Workdays are the 24 hour periods, or 23 and 25 hours across daylight saving
+/// time transitions, starting at the workweek's start time each day.
+fn get_workday_containing(
+    time: DateTime<Utc>,
+    time_zone: Tz,
+    start_time: NaiveTime,
+) -> (DateTime<Utc>, DateTime<Utc>, NaiveDate) {
+    let local_time = time.with_timezone(&time_zone).naive_local();
+    let mut workday_date = local_time.date();
+    if local_time < workday_date.and_time(start_time) {
+        workday_date = workday_date - Days::new(1);
+    }
+
+    let (start_of_workday, end_of_workday) = resolve_period_containing(
+        time,
+        workday_date.and_time(start_time),
+        (workday_date + Days::new(1)).and_time(start_time),
+        time_zone,
+    );
+    (start_of_workday, end_of_workday, workday_date)
+}
+
+/// Converts the local start and end of a week or workday containing `time` to UTC.
+///
+/// When clocks fall back and a boundary's local time happens twice, the occurrence is picked so
+/// the period still contains `time`: the later start when `time` is already past it, and the
+/// earlier end unless `time` is already past it. Otherwise a time in the repeated hour could land
+/// after the end of its own period.
+fn resolve_period_containing(
+    time: DateTime<Utc>,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    time_zone: Tz,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = match time_zone.from_local_datetime(&start) {
+        LocalResult::Ambiguous(_, latest) if latest.to_utc() <= time => latest.to_utc(),
+        _ => resolve_local_time(start, time_zone),
+    };
+    let end = match time_zone.from_local_datetime(&end) {
+        LocalResult::Ambiguous(earliest, latest) if earliest.to_utc() <= time => latest.to_utc(),
+        _ => resolve_local_time(end, time_zone),
+    };
+
+    (start, end)
+}
+
+/// Converts a local wall clock time to UTC.
+///
+/// When clocks fall back and the local time happens twice, the first occurrence is used. When
+/// clocks spring forward over the local time, the moment the clocks jump is used, which is the
+/// first local time that exists after it.
+fn resolve_local_time(local_time: NaiveDateTime, time_zone: Tz) -> DateTime<Utc> {
+    match time_zone.from_local_datetime(&local_time) {
+        LocalResult::Single(time) => time.to_utc(),
+        LocalResult::Ambiguous(earliest, _) => earliest.to_utc(),
+        LocalResult::None => {
+            // a gap is never longer than a day so the offset from the day before is the offset in
+            // effect right before the clocks jumped
+            let offset_before_gap = time_zone
+                .offset_from_utc_datetime(&(local_time - Days::new(1)))
+                .fix();
+            (local_time - offset_before_gap).and_utc()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{path::PathBuf, str::FromStr};
+    use std::str::FromStr;
 
-    use chrono::SecondsFormat;
-    use chrono::TimeZone;
+    use chrono::{SecondsFormat, Weekday};
     use chrono_tz::US::Central;
 
     use super::*;
+    use crate::employee_shift::{
+        config::{
+            AmbiguousTimePolicy, AutomaticBreak, BreakRules, OvertimePolicy, RoundingPolicy,
+            TimestampFormat,
+        },
+        employee::BreakViolationKind,
+    };
+
+    /// A shift worked by employee 1 between two RFC 3339 timestamps
+    fn shift(shift_id: u64, start_time: &str, end_time: &str) -> EmployeeShift {
+        EmployeeShift::test_shift(
+            shift_id,
+            DateTime::parse_from_rfc3339(start_time).unwrap().into(),
+            DateTime::parse_from_rfc3339(end_time).unwrap().into(),
+        )
+    }
 
     #[test]
-    fn test_summarize_shifts_from_json_file() {
+    fn test_summarize_shifts_from_file() {
         let path = PathBuf::from_str("./test_datasets/test_dataset_multiple.json").unwrap();
-        let summaries = summarize_shifts_from_json_file(&path).unwrap();
+        let summaries = summarize_shifts_from_file(&path, &SummaryConfig::default())
+            .unwrap()
+            .summaries;
         assert_eq!(
             summaries
                 .iter()
@@ -282,14 +1070,148 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_summarize_shifts_in_pacific_time() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_time_zones.json").unwrap();
+        let config = SummaryConfig {
+            time_zone: chrono_tz::America::Los_Angeles,
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        let hours_for_week = |start_of_week: &str| {
+            summaries
+                .iter()
+                .find(|item| item.employee_id == 41488322 && item.start_of_week == start_of_week)
+                .unwrap()
+                .regular_hours
+        };
+
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(hours_for_week("2024-06-23"), 2.);
+        assert_eq!(hours_for_week("2023-12-31"), 0.5);
+        assert_eq!(hours_for_week("2024-01-07"), 0.5);
+    }
+
+    #[test]
+    fn test_summarize_shifts_in_eastern_time() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_time_zones.json").unwrap();
+        let config = SummaryConfig {
+            time_zone: chrono_tz::America::New_York,
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        let hours_for_week = |start_of_week: &str| {
+            summaries
+                .iter()
+                .find(|item| item.employee_id == 41488322 && item.start_of_week == start_of_week)
+                .unwrap()
+                .regular_hours
+        };
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(hours_for_week("2024-06-30"), 2.);
+        assert_eq!(hours_for_week("2024-01-07"), 1.);
+    }
+
+    #[test]
+    fn test_summarize_shifts_in_arizona_time() {
+        // Arizona doesn't observe daylight saving time, so it lines up with Pacific time in the
+        // summer and with Mountain time in the winter
+        let path = PathBuf::from_str("./test_datasets/test_dataset_time_zones.json").unwrap();
+        let config = SummaryConfig {
+            time_zone: chrono_tz::America::Phoenix,
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        let hours_for_week = |start_of_week: &str| {
+            summaries
+                .iter()
+                .find(|item| item.employee_id == 41488322 && item.start_of_week == start_of_week)
+                .unwrap()
+                .regular_hours
+        };
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(hours_for_week("2024-06-23"), 2.);
+        assert_eq!(hours_for_week("2024-01-07"), 1.);
+    }
+
+    #[test]
+    fn test_summarize_shifts_in_central_time() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_time_zones.json").unwrap();
+        let summaries = summarize_shifts_from_file(&path, &SummaryConfig::default())
+            .unwrap()
+            .summaries;
+
+        let hours_for_week = |start_of_week: &str| {
+            summaries
+                .iter()
+                .find(|item| item.employee_id == 41488322 && item.start_of_week == start_of_week)
+                .unwrap()
+                .regular_hours
+        };
+
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(hours_for_week("2024-06-23"), 0.5);
+        assert_eq!(hours_for_week("2024-06-30"), 1.5);
+        assert_eq!(hours_for_week("2024-01-07"), 1.);
+    }
+
+    #[test]
+    fn test_summarize_shifts_with_employee_time_zones() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_employee_time_zones.json").unwrap();
+        let mut config = SummaryConfig::default();
+        config
+            .load_employee_time_zones(
+                &PathBuf::from_str("./test_datasets/employee_time_zones.json").unwrap(),
+            )
+            .unwrap();
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        let hours_for_employee_week = |employee_id: u64, start_of_week: &str| {
+            summaries
+                .iter()
+                .find(|item| item.employee_id == employee_id && item.start_of_week == start_of_week)
+                .unwrap()
+                .regular_hours
+        };
+
+        assert_eq!(summaries.len(), 4);
+        // employee 1 isn't mapped so falls back to central time
+        assert_eq!(hours_for_employee_week(1, "2024-06-23"), 0.5);
+        assert_eq!(hours_for_employee_week(1, "2024-06-30"), 1.5);
+        // employee 2 works on the west coast
+        assert_eq!(hours_for_employee_week(2, "2024-06-23"), 2.);
+        // the shift's own time zone takes precedence over the employee's time zone
+        assert_eq!(hours_for_employee_week(3, "2024-06-30"), 2.);
+    }
+
     #[test]
     fn test_calculate_overtime_hours() {
         let path = PathBuf::from_str("./test_datasets/test_dataset_overtime_hours.json").unwrap();
-        let shifts = read_shifts(&path).unwrap();
+        let (shifts, _) =
+            read_shifts(&path, Some(InputFormat::Json), &SummaryConfig::default()).unwrap();
 
-        let summaries = summarize_all_employee_hours(shifts);
+        let summaries = summarize_all_employee_hours(
+            shifts,
+            &SummaryConfig::default(),
+            &mut ValidationReport::default(),
+            &mut vec![],
+        );
         let mut summaries: Vec<EmployeeShiftSummary> = summaries.into_values().collect();
-        calculate_overtime_hours(&mut summaries);
+        calculate_overtime_hours(&mut summaries, &OvertimePolicy::default());
 
         let summary_week_06_30_2024_41488322_employee = summaries
             .iter()
@@ -338,26 +1260,294 @@ mod tests {
     }
 
     #[test]
-    fn test_employee_with_overlapping_shifts() {
-        let path =
-            PathBuf::from_str("./test_datasets/test_dataset_overlapping_shift.json").unwrap();
-        let shifts = read_shifts(&path).unwrap();
+    fn test_calculate_overtime_hours_with_double_time_tier() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_overtime_hours.json").unwrap();
+        let config = SummaryConfig {
+            overtime_policy: OvertimePolicy {
+                weekly_overtime_threshold: 44.,
+                weekly_double_time_threshold: Some(48.),
+                daily: None,
+                seventh_day: None,
+            },
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
 
-        let summaries = summarize_all_employee_hours(shifts);
-        let summary_first_week = summaries
-            .get(&(41488322, NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()))
+        let summary_week_06_30_2024_41488322_employee = summaries
+            .iter()
+            .find(|summary| {
+                summary.employee_id == 41488322 && summary.start_of_week == "2024-06-30"
+            })
             .unwrap();
-        let summary_second_week = summaries
-            .get(&(41488322, NaiveDate::from_ymd_opt(2024, 7, 7).unwrap()))
+        let summary_week_07_07_2024_4_employee = summaries
+            .iter()
+            .find(|summary| summary.employee_id == 4 && summary.start_of_week == "2024-07-07")
+            .unwrap();
+
+        assert_eq!(summary_week_06_30_2024_41488322_employee.regular_hours, 44.);
+        assert_eq!(summary_week_06_30_2024_41488322_employee.overtime_hours, 4.);
+        assert_eq!(
+            summary_week_06_30_2024_41488322_employee.double_time_hours,
+            2.
+        );
+        assert_eq!(summary_week_07_07_2024_4_employee.regular_hours, 43.5);
+        assert_eq!(summary_week_07_07_2024_4_employee.overtime_hours, 0.);
+        assert_eq!(summary_week_07_07_2024_4_employee.double_time_hours, 0.);
+    }
+
+    #[test]
+    fn test_calculate_california_daily_and_weekly_overtime_hours() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_daily_overtime.json").unwrap();
+        let config = SummaryConfig {
+            overtime_policy: OvertimePolicy::california(),
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        let summary = summaries
+            .iter()
+            .find(|summary| summary.employee_id == 1 && summary.start_of_week == "2024-07-07")
+            .unwrap();
+        let day = |date: &str| summary.days.iter().find(|day| day.date == date).unwrap();
+
+        // monday and tuesday go over 8 and 12 hours in a day, and saturday pushes the regular
+        // hours over 40 for the week
+        assert_eq!(summary.regular_hours, 40.);
+        assert_eq!(summary.overtime_hours, 12.);
+        assert_eq!(summary.double_time_hours, 1.);
+        assert_eq!(summary.days.len(), 6);
+        assert_eq!(day("2024-07-08").regular_hours, 8.);
+        assert_eq!(day("2024-07-08").overtime_hours, 2.);
+        assert_eq!(day("2024-07-09").regular_hours, 8.);
+        assert_eq!(day("2024-07-09").overtime_hours, 4.);
+        assert_eq!(day("2024-07-09").double_time_hours, 1.);
+        assert_eq!(day("2024-07-12").regular_hours, 8.);
+        assert_eq!(day("2024-07-13").regular_hours, 0.);
+        assert_eq!(day("2024-07-13").overtime_hours, 6.);
+
+        // a 12 hour shift split over two workdays isn't overtime
+        let summary = summaries
+            .iter()
+            .find(|summary| summary.employee_id == 2 && summary.start_of_week == "2024-07-07")
+            .unwrap();
+        assert_eq!(summary.regular_hours, 12.);
+        assert_eq!(summary.overtime_hours, 0.);
+        assert_eq!(summary.days[0].date, "2024-07-12");
+        assert_eq!(summary.days[0].regular_hours, 6.);
+        assert_eq!(summary.days[1].date, "2024-07-13");
+        assert_eq!(summary.days[1].regular_hours, 6.);
+    }
+
+    #[test]
+    fn test_calculate_seventh_consecutive_day_overtime_hours() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_seventh_day.json").unwrap();
+        let config = SummaryConfig {
+            overtime_policy: OvertimePolicy::california(),
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        // worked every day of the week, so the 9 hours on saturday are 8 hours of overtime and 1
+        // hour of double time instead of 8 regular hours and 1 hour of overtime
+        let summary = summaries
+            .iter()
+            .find(|summary| summary.employee_id == 1 && summary.start_of_week == "2024-07-07")
+            .unwrap();
+        assert_eq!(summary.days.len(), 7);
+        assert_eq!(summary.regular_hours, 36.);
+        assert_eq!(summary.overtime_hours, 8.);
+        assert_eq!(summary.double_time_hours, 1.);
+        assert_eq!(summary.seventh_day_hours, 9.);
+        assert_eq!(summary.days[6].overtime_hours, 8.);
+        assert_eq!(summary.days[6].double_time_hours, 1.);
+
+        // only worked six days, so saturday follows the daily rules
+        let summary = summaries
+            .iter()
+            .find(|summary| summary.employee_id == 2 && summary.start_of_week == "2024-07-07")
+            .unwrap();
+        assert_eq!(summary.days.len(), 6);
+        assert_eq!(summary.regular_hours, 40.);
+        assert_eq!(summary.overtime_hours, 14.);
+        assert_eq!(summary.double_time_hours, 0.);
+        assert_eq!(summary.seventh_day_hours, 0.);
+    }
+
+    #[test]
+    fn test_employee_with_overlapping_shifts() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_overlapping_shift.json").unwrap();
+        let (shifts, _) =
+            read_shifts(&path, Some(InputFormat::Json), &SummaryConfig::default()).unwrap();
+
+        let summaries = summarize_all_employee_hours(
+            shifts,
+            &SummaryConfig::default(),
+            &mut ValidationReport::default(),
+            &mut vec![],
+        );
+        let summary_first_week = summaries
+            .get(&(41488322, NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()))
+            .unwrap();
+        let summary_second_week = summaries
+            .get(&(41488322, NaiveDate::from_ymd_opt(2024, 7, 7).unwrap()))
             .unwrap();
 
         assert_eq!(summaries.len(), 2);
 
         assert_eq!(summary_first_week.regular_hours, 12.5);
-        assert_eq!(&summary_first_week.invalid_shifts, &[2663141019]);
+        assert_eq!(
+            &summary_first_week.invalid_shifts,
+            &[InvalidShift {
+                shift_id: 2663141019,
+                reason: InvalidShiftReason::Overlapping,
+                conflicting_shift_ids: vec![2663141013],
+            }]
+        );
 
         assert_eq!(summary_second_week.regular_hours, 0.);
-        assert_eq!(&summary_second_week.invalid_shifts, &[2663141013]);
+        assert_eq!(
+            &summary_second_week.invalid_shifts,
+            &[InvalidShift {
+                shift_id: 2663141013,
+                reason: InvalidShiftReason::Overlapping,
+                conflicting_shift_ids: vec![2663141019],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_overlapping_shifts() {
+        let shifts = [
+            // encompasses shifts 2 and 3
+            shift(1, "2024-07-01T08:00:00Z", "2024-07-01T20:00:00Z"),
+            shift(2, "2024-07-01T09:00:00Z", "2024-07-01T10:00:00Z"),
+            shift(3, "2024-07-01T19:00:00Z", "2024-07-01T21:00:00Z"),
+            // starts right when shift 3 ends
+            shift(4, "2024-07-01T21:00:00Z", "2024-07-01T23:00:00Z"),
+            // same times as each other
+            shift(5, "2024-07-02T08:00:00Z", "2024-07-02T16:00:00Z"),
+            shift(6, "2024-07-02T08:00:00Z", "2024-07-02T16:00:00Z"),
+            shift(7, "2024-07-03T08:00:00Z", "2024-07-03T16:00:00Z"),
+        ];
+
+        assert_eq!(
+            find_overlapping_shifts(&shifts),
+            [
+                vec![2, 3],
+                vec![1],
+                vec![1],
+                vec![],
+                vec![6],
+                vec![5],
+                vec![]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_rest_violations() {
+        let shifts = [
+            shift(1, "2024-07-01T12:00:00Z", "2024-07-01T22:00:00Z"),
+            // 8 hours after shift 1
+            shift(2, "2024-07-02T06:00:00Z", "2024-07-02T14:00:00Z"),
+            // exactly 10 hours after shift 2
+            shift(3, "2024-07-03T00:00:00Z", "2024-07-03T01:00:00Z"),
+            // ends before the 10 hours of rest would have
+            shift(4, "2024-07-03T02:00:00Z", "2024-07-03T04:00:00Z"),
+        ];
+        let shifts = shifts.iter().collect::<Vec<_>>();
+
+        let violations = find_rest_violations(&shifts, Hours::from_hours(10.))
+            .into_iter()
+            .map(|(_, violation)| violation)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            [
+                RestViolation {
+                    shift_id: 2,
+                    previous_shift_id: 1,
+                    rest_hours: Hours::from_hours(8.),
+                    premium_hours: Hours::from_hours(2.),
+                },
+                RestViolation {
+                    shift_id: 4,
+                    previous_shift_id: 3,
+                    rest_hours: Hours::from_hours(1.),
+                    premium_hours: Hours::from_hours(2.),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rest_too_long_to_add_to_a_time_covers_the_whole_shift() {
+        let shifts = [
+            shift(1, "2024-07-01T12:00:00Z", "2024-07-01T22:00:00Z"),
+            shift(2, "2024-07-02T06:00:00Z", "2024-07-02T14:00:00Z"),
+        ];
+        let shifts = shifts.iter().collect::<Vec<_>>();
+
+        let violations = find_rest_violations(&shifts, Hours::MAX)
+            .into_iter()
+            .map(|(_, violation)| violation)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            [RestViolation {
+                shift_id: 2,
+                previous_shift_id: 1,
+                rest_hours: Hours::from_hours(8.),
+                premium_hours: Hours::from_hours(8.),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_summarize_shifts_with_minimum_rest_between_shifts() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_rest_between_shifts.json").unwrap();
+        let config = SummaryConfig {
+            minimum_rest_hours: Some(10.),
+            ..SummaryConfig::default()
+        };
+        let mut summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+        summaries.sort_by_key(|summary| (summary.employee_id, summary.start_of_week.clone()));
+
+        assert_eq!(summaries.len(), 3);
+        assert!(summaries[0].rest_violations.is_empty());
+
+        // shift 5 starts on Sunday, 5 hours after shift 4 ended on Saturday night
+        let first_week = &summaries[1];
+        let second_week = &summaries[2];
+        assert_eq!(
+            first_week
+                .rest_violations
+                .iter()
+                .map(|violation| (violation.previous_shift_id, violation.shift_id))
+                .collect::<Vec<_>>(),
+            [(1, 2)]
+        );
+        assert_eq!(first_week.rest_premium_hours, 2.);
+        assert_eq!(
+            second_week
+                .rest_violations
+                .iter()
+                .map(|violation| (violation.previous_shift_id, violation.shift_id))
+                .collect::<Vec<_>>(),
+            [(4, 5)]
+        );
+        assert_eq!(second_week.rest_violations[0].rest_hours, 5.);
+        assert_eq!(second_week.rest_premium_hours, 5.);
     }
 
     #[test]
@@ -365,9 +1555,15 @@ mod tests {
         let path =
             PathBuf::from_str("./test_datasets/test_dataset_shift_crosses_sunday_midnight.json")
                 .unwrap();
-        let shifts = read_shifts(&path).unwrap();
+        let (shifts, _) =
+            read_shifts(&path, Some(InputFormat::Json), &SummaryConfig::default()).unwrap();
 
-        let summaries = summarize_all_employee_hours(shifts);
+        let summaries = summarize_all_employee_hours(
+            shifts,
+            &SummaryConfig::default(),
+            &mut ValidationReport::default(),
+            &mut vec![],
+        );
         assert_eq!(summaries.len(), 2);
         assert_eq!(
             summaries
@@ -401,7 +1597,7 @@ mod tests {
             expected_start_week_for_end_time,
             _,
             _,
-        ) = get_start_of_week_for_shift(start_time, end_time);
+        ) = get_start_of_week_for_shift(start_time, end_time, Central, &WorkWeek::default());
         assert_eq!(
             expected_start_week_for_start_time,
             Central
@@ -442,7 +1638,7 @@ mod tests {
             expected_start_week_for_end_time,
             _,
             _,
-        ) = get_start_of_week_for_shift(start_time, end_time);
+        ) = get_start_of_week_for_shift(start_time, end_time, Central, &WorkWeek::default());
         assert_eq!(
             expected_start_week_for_start_time,
             Central
@@ -467,10 +1663,313 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_summarize_shifts_during_daylight_saving_time_transitions() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_daylight_saving_time.json").unwrap();
+        let summaries = summarize_shifts_from_file(&path, &SummaryConfig::default())
+            .unwrap()
+            .summaries;
+
+        let hours_for_employee_week = |employee_id: u64, start_of_week: &str| {
+            summaries
+                .iter()
+                .find(|item| item.employee_id == employee_id && item.start_of_week == start_of_week)
+                .unwrap()
+                .regular_hours
+        };
+
+        assert_eq!(summaries.len(), 3);
+        // spring forward skips 2 AM, so the 1 AM to 5 AM shift is only 3 hours long, plus the
+        // 2 hour shift later in the week
+        assert_eq!(hours_for_employee_week(1, "2024-03-10"), 5.);
+        // fall back repeats 1 AM, so the shift crossing sunday midnight has 7 hours in the
+        // second week even though it ends at 6 AM, plus the 2 hour shift later in the week
+        assert_eq!(hours_for_employee_week(2, "2024-10-27"), 2.);
+        assert_eq!(hours_for_employee_week(2, "2024-11-03"), 9.);
+    }
+
+    #[test]
+    fn test_get_week_boundaries_for_weeks_with_daylight_saving_time_transitions() {
+        let time: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-03-12T07:30:00.000000Z")
+            .unwrap()
+            .into();
+        let (start_of_week, end_of_week, start_of_week_date) =
+            get_week_containing(time, Central, &WorkWeek::default());
+        assert_eq!(
+            start_of_week,
+            Central
+                .with_ymd_and_hms(2024, 3, 10, 0, 0, 0)
+                .unwrap()
+                .to_utc()
+        );
+        assert_eq!(
+            end_of_week,
+            Central
+                .with_ymd_and_hms(2024, 3, 17, 0, 0, 0)
+                .unwrap()
+                .to_utc()
+        );
+        assert_eq!((end_of_week - start_of_week).num_hours(), 167);
+        assert_eq!(
+            start_of_week_date,
+            NaiveDate::from_ymd_opt(2024, 3, 10).unwrap()
+        );
+
+        let time: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-11-05T07:30:00.000000Z")
+            .unwrap()
+            .into();
+        let (start_of_week, end_of_week, start_of_week_date) =
+            get_week_containing(time, Central, &WorkWeek::default());
+        assert_eq!((end_of_week - start_of_week).num_hours(), 169);
+        assert_eq!(
+            start_of_week_date,
+            NaiveDate::from_ymd_opt(2024, 11, 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_week_boundaries_for_work_week_starting_monday() {
+        let work_week = WorkWeek {
+            start_day: Weekday::Mon,
+            start_time: NaiveTime::MIN,
+        };
+
+        // sunday belongs to the week that started the monday before
+        let time: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-07-07T17:00:00.000000Z")
+            .unwrap()
+            .into();
+        let (start_of_week, end_of_week, start_of_week_date) =
+            get_week_containing(time, Central, &work_week);
+        assert_eq!(
+            start_of_week,
+            Central
+                .with_ymd_and_hms(2024, 7, 1, 0, 0, 0)
+                .unwrap()
+                .to_utc()
+        );
+        assert_eq!(
+            end_of_week,
+            Central
+                .with_ymd_and_hms(2024, 7, 8, 0, 0, 0)
+                .unwrap()
+                .to_utc()
+        );
+        assert_eq!(
+            start_of_week_date,
+            NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_week_boundaries_for_work_week_starting_wednesday_morning() {
+        let work_week = WorkWeek {
+            start_day: Weekday::Wed,
+            start_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+        };
+
+        // 5 AM on a wednesday is still part of the previous week
+        let time: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-07-03T10:00:00.000000Z")
+            .unwrap()
+            .into();
+        let (start_of_week, end_of_week, start_of_week_date) =
+            get_week_containing(time, Central, &work_week);
+        assert_eq!(
+            start_of_week,
+            Central
+                .with_ymd_and_hms(2024, 6, 26, 6, 0, 0)
+                .unwrap()
+                .to_utc()
+        );
+        assert_eq!(
+            end_of_week,
+            Central
+                .with_ymd_and_hms(2024, 7, 3, 6, 0, 0)
+                .unwrap()
+                .to_utc()
+        );
+        assert_eq!(
+            start_of_week_date,
+            NaiveDate::from_ymd_opt(2024, 6, 26).unwrap()
+        );
+
+        // 6 AM starts the next week
+        let time: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-07-03T11:00:00.000000Z")
+            .unwrap()
+            .into();
+        let (start_of_week, _, start_of_week_date) = get_week_containing(time, Central, &work_week);
+        assert_eq!(
+            start_of_week,
+            Central
+                .with_ymd_and_hms(2024, 7, 3, 6, 0, 0)
+                .unwrap()
+                .to_utc()
+        );
+        assert_eq!(
+            start_of_week_date,
+            NaiveDate::from_ymd_opt(2024, 7, 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_summarize_shifts_with_work_week_starting_wednesday_morning() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_shift_crosses_sunday_midnight.json")
+                .unwrap();
+        let config = SummaryConfig {
+            work_week: WorkWeek {
+                start_day: Weekday::Wed,
+                start_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            },
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        let hours_for_week = |start_of_week: &str| {
+            summaries
+                .iter()
+                .find(|item| item.employee_id == 41488322 && item.start_of_week == start_of_week)
+                .unwrap()
+                .regular_hours
+        };
+
+        // the shift crossing sunday midnight no longer crosses a week boundary, while the shift
+        // from wednesday evening into thursday morning now starts in the week of 2024-07-03
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(hours_for_week("2024-07-03"), 33.);
+    }
+
+    #[test]
+    fn test_get_week_boundaries_when_local_midnight_does_not_exist() {
+        // Chile springs forward at midnight, so sunday 2024-09-08 starts at 1 AM
+        let time: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-09-10T12:00:00.000000Z")
+            .unwrap()
+            .into();
+        let (start_of_week, _, start_of_week_date) =
+            get_week_containing(time, chrono_tz::America::Santiago, &WorkWeek::default());
+
+        assert_eq!(
+            start_of_week,
+            DateTime::parse_from_rfc3339("2024-09-08T04:00:00.000000Z").unwrap()
+        );
+        assert_eq!(
+            start_of_week_date,
+            NaiveDate::from_ymd_opt(2024, 9, 8).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_period_spanning_three_weeks_is_split_between_all_of_them() {
+        let utc = |time: &str| DateTime::parse_from_rfc3339(time).unwrap().to_utc();
+
+        // midnight on Wednesday 2024-07-03 to midnight on Saturday 2024-07-20, Central time
+        let mut summaries = HashMap::new();
+        add_period_to_summaries(
+            &mut summaries,
+            1,
+            utc("2024-07-03T05:00:00Z"),
+            utc("2024-07-20T05:00:00Z"),
+            Central,
+            &WorkWeek::default(),
+        );
+        let hours_for_week = |date: NaiveDate| summaries[&(1, date)].regular_hours;
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(
+            hours_for_week(NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()),
+            96.
+        );
+        assert_eq!(
+            hours_for_week(NaiveDate::from_ymd_opt(2024, 7, 7).unwrap()),
+            168.
+        );
+        assert_eq!(
+            hours_for_week(NaiveDate::from_ymd_opt(2024, 7, 14).unwrap()),
+            144.
+        );
+    }
+
+    #[test]
+    fn test_weeks_starting_in_the_repeated_hour_when_clocks_fall_back() {
+        let work_week = WorkWeek {
+            start_day: Weekday::Sun,
+            start_time: NaiveTime::from_hms_opt(1, 30, 0).unwrap(),
+        };
+        let utc = |time: &str| DateTime::parse_from_rfc3339(time).unwrap().to_utc();
+
+        // 07:15 UTC is the second 1:15 AM on 2024-11-03, which is before the week starts at the
+        // second 1:30 AM
+        let (start_of_week, end_of_week, start_of_week_date) =
+            get_week_containing(utc("2024-11-03T07:15:00Z"), Central, &work_week);
+        assert_eq!(start_of_week, utc("2024-10-27T06:30:00Z"));
+        assert_eq!(end_of_week, utc("2024-11-03T07:30:00Z"));
+        assert_eq!(
+            start_of_week_date,
+            NaiveDate::from_ymd_opt(2024, 10, 27).unwrap()
+        );
+
+        let mut summaries = HashMap::new();
+        add_period_to_summaries(
+            &mut summaries,
+            1,
+            utc("2024-11-03T07:15:00Z"),
+            utc("2024-11-03T09:15:00Z"),
+            Central,
+            &work_week,
+        );
+        let hours_for_week = |date: NaiveDate| summaries[&(1, date)].regular_hours;
+        assert_eq!(
+            hours_for_week(NaiveDate::from_ymd_opt(2024, 10, 27).unwrap()),
+            0.25
+        );
+        assert_eq!(
+            hours_for_week(NaiveDate::from_ymd_opt(2024, 11, 3).unwrap()),
+            1.75
+        );
+    }
+
+    #[test]
+    fn test_workdays_starting_in_the_repeated_hour_when_clocks_fall_back() {
+        let work_week = WorkWeek {
+            start_day: Weekday::Mon,
+            start_time: NaiveTime::from_hms_opt(1, 30, 0).unwrap(),
+        };
+        let utc = |time: &str| DateTime::parse_from_rfc3339(time).unwrap().to_utc();
+
+        // 07:15 UTC is the second 1:15 AM on 2024-11-03, which is before that day's workday
+        // starts at the second 1:30 AM
+        let (start_of_workday, end_of_workday, workday_date) =
+            get_workday_containing(utc("2024-11-03T07:15:00Z"), Central, work_week.start_time);
+        assert_eq!(start_of_workday, utc("2024-11-02T06:30:00Z"));
+        assert_eq!(end_of_workday, utc("2024-11-03T07:30:00Z"));
+        assert_eq!(workday_date, NaiveDate::from_ymd_opt(2024, 11, 2).unwrap());
+
+        let mut summary =
+            EmployeeShiftSummary::new(1, NaiveDate::from_ymd_opt(2024, 10, 28).unwrap());
+        add_hours_to_summary(
+            &mut summary,
+            utc("2024-11-03T07:15:00Z"),
+            utc("2024-11-03T09:15:00Z"),
+            Central,
+            &work_week,
+        );
+        assert_eq!(summary.regular_hours, 2.);
+        assert_eq!(
+            summary
+                .days
+                .iter()
+                .map(|day| (day.date.as_str(), day.regular_hours.as_f64()))
+                .collect::<Vec<_>>(),
+            [("2024-11-02", 0.25), ("2024-11-03", 1.75)]
+        );
+    }
+
     #[test]
     fn test_read_employee_shift() {
         let path = PathBuf::from_str("./test_datasets/test_dataset.json").unwrap();
-        let shifts = read_shifts(&path).unwrap();
+        let (shifts, _) =
+            read_shifts(&path, Some(InputFormat::Json), &SummaryConfig::default()).unwrap();
 
         assert_eq!(shifts[0].shift_id, 2663141019);
         assert_eq!(shifts[0].employee_id, 41488322);
@@ -492,7 +1991,8 @@ mod tests {
     #[test]
     fn test_read_multiple_employee_shifts() {
         let path = PathBuf::from_str("./test_datasets/test_dataset_multiple.json").unwrap();
-        let shifts = read_shifts(&path).unwrap();
+        let (shifts, _) =
+            read_shifts(&path, Some(InputFormat::Json), &SummaryConfig::default()).unwrap();
 
         assert_eq!(shifts.len(), 3);
 
@@ -527,6 +2027,759 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_read_csv_employee_shifts() {
+        let json_path = PathBuf::from_str("./test_datasets/test_dataset_multiple.json").unwrap();
+        let csv_path = PathBuf::from_str("./test_datasets/test_dataset_multiple.csv").unwrap();
+        let (json_shifts, _) = read_shifts(
+            &json_path,
+            Some(InputFormat::Json),
+            &SummaryConfig::default(),
+        )
+        .unwrap();
+        let (csv_shifts, _) =
+            read_shifts(&csv_path, Some(InputFormat::Csv), &SummaryConfig::default()).unwrap();
+
+        assert_eq!(csv_shifts.len(), 3);
+        for (csv_shift, json_shift) in csv_shifts.iter().zip(&json_shifts) {
+            assert!(csv_shift.has_same_data(json_shift));
+        }
+    }
+
+    #[test]
+    fn test_csv_timestamps_keep_leading_zeros() {
+        let csv = "ShiftID,EmployeeID,StartTime,EndTime\n1,2,070320240600,070320241400\n";
+        let records: Vec<_> = read_csv_records(
+            csv.as_bytes(),
+            Arc::from("test.csv"),
+            &FieldNames::default(),
+        )
+        .unwrap()
+        .collect();
+        let raw_shift = records[0].1.as_ref().unwrap();
+        assert_eq!(raw_shift.start_time, "070320240600");
+
+        let config = SummaryConfig {
+            timestamp_format: TimestampFormat::Pattern("%m%d%Y%H%M".to_string()),
+            ..Default::default()
+        };
+        let shift = validate_shift(raw_shift, &Arc::from("test.csv"), 1, &config).unwrap();
+        assert_eq!(
+            shift.start_time,
+            DateTime::parse_from_rfc3339("2024-07-03T11:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_csv_rows_are_reported_by_row_number() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_invalid_records.csv").unwrap();
+        let config = SummaryConfig {
+            validation_mode: ValidationMode::Report,
+            ..Default::default()
+        };
+        // the format is detected from the file extension
+        let ShiftSummaries {
+            summaries,
+            validation_report,
+            ..
+        } = summarize_shifts_from_file(&path, &config).unwrap();
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].regular_hours, 12.5);
+
+        assert_eq!(validation_report.errors.len(), 3);
+        assert!(matches!(
+            validation_report.errors[0],
+            ShiftError::BadTimestamp {
+                record: 2,
+                shift_id: 2,
+                ..
+            }
+        ));
+        assert!(matches!(
+            validation_report.errors[1],
+            ShiftError::Parse { record: 3, .. }
+        ));
+        assert!(matches!(
+            validation_report.errors[2],
+            ShiftError::BadTimeZone {
+                record: 4,
+                shift_id: 4,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_read_ndjson_employee_shifts() {
+        let json_path = PathBuf::from_str("./test_datasets/test_dataset_multiple.json").unwrap();
+        let ndjson_path =
+            PathBuf::from_str("./test_datasets/test_dataset_multiple.ndjson").unwrap();
+        let (json_shifts, _) = read_shifts(
+            &json_path,
+            Some(InputFormat::Json),
+            &SummaryConfig::default(),
+        )
+        .unwrap();
+        let (ndjson_shifts, _) = read_shifts(
+            &ndjson_path,
+            Some(InputFormat::Ndjson),
+            &SummaryConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(ndjson_shifts.len(), 3);
+        for (ndjson_shift, json_shift) in ndjson_shifts.iter().zip(&json_shifts) {
+            assert!(ndjson_shift.has_same_data(json_shift));
+        }
+    }
+
+    #[test]
+    fn test_input_field_names() {
+        let config = SummaryConfig {
+            field_names: FieldNames {
+                shift_id: Some("shift_id".to_string()),
+                employee_id: Some("emp".to_string()),
+                start_time: Some("clock_in".to_string()),
+                end_time: Some("clock_out".to_string()),
+                time_zone: Some("location_tz".to_string()),
+                breaks: None,
+            },
+            ..Default::default()
+        };
+
+        for path in [
+            "./test_datasets/test_dataset_field_names.json",
+            "./test_datasets/test_dataset_field_names.csv",
+        ] {
+            let path = PathBuf::from_str(path).unwrap();
+            let ShiftSummaries {
+                summaries,
+                shift_details,
+                ..
+            } = summarize_shifts_from_file(&path, &config).unwrap();
+
+            assert_eq!(summaries.len(), 1);
+            assert_eq!(summaries[0].employee_id, 41488322);
+            assert_eq!(summaries[0].regular_hours, 12.5);
+            assert_eq!(shift_details[1].time_zone, chrono_tz::America::Los_Angeles);
+
+            // without the field names the usual keys are missing
+            let err = summarize_shifts_from_file(&path, &SummaryConfig::default()).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ShiftError>(),
+                Some(ShiftError::Parse { record: 1, .. })
+            ));
+        }
+
+        let line = r#"{"shift_id": 1, "emp": 2, "clock_in": "2021-08-30T12:30:00Z", "clock_out": "2021-08-30T21:00:00Z"}"#;
+        let shift = parse_json_line(line.as_bytes(), &config.field_names).unwrap();
+        assert_eq!(shift.shift_id, 1);
+        assert_eq!(shift.employee_id, 2);
+        assert_eq!(shift.start_time, "2021-08-30T12:30:00Z");
+    }
+
+    #[test]
+    fn test_renamed_fields_can_swap_names() {
+        // an input that has the start and end times the wrong way round
+        let field_names = FieldNames {
+            start_time: Some("EndTime".to_string()),
+            end_time: Some("StartTime".to_string()),
+            ..Default::default()
+        };
+        let mut record = serde_json::json!({
+            "ShiftID": 1,
+            "StartTime": "2021-08-30T21:00:00Z",
+            "EndTime": "2021-08-30T12:30:00Z",
+        });
+        rename_fields(&mut record, &field_names);
+
+        assert_eq!(
+            record,
+            serde_json::json!({
+                "ShiftID": 1,
+                "StartTime": "2021-08-30T12:30:00Z",
+                "EndTime": "2021-08-30T21:00:00Z",
+            })
+        );
+        assert_eq!(field_names.field_name("EndTime"), "StartTime");
+        assert_eq!(field_names.field_name("ShiftID"), "ShiftID");
+    }
+
+    #[test]
+    fn test_epoch_second_timestamps() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_epoch_seconds.json").unwrap();
+        let config = SummaryConfig {
+            timestamp_format: TimestampFormat::EpochSeconds,
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        // epoch seconds can be JSON numbers or strings
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].start_of_week, "2024-06-30");
+        assert_eq!(summaries[0].regular_hours, 15.5);
+
+        let err = summarize_shifts_from_file(&path, &SummaryConfig::default()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ShiftError>().unwrap().to_string(),
+            "./test_datasets/test_dataset_epoch_seconds.json record 1, shift 1: StartTime \
+             \"1720004400\" is not an RFC 3339 timestamp"
+        );
+    }
+
+    #[test]
+    fn test_local_timestamps() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_local_timestamps.csv").unwrap();
+        let config = SummaryConfig {
+            timestamp_format: TimestampFormat::Local,
+            validation_mode: ValidationMode::Report,
+            ..Default::default()
+        };
+        let ShiftSummaries {
+            summaries,
+            validation_report,
+            shift_details,
+        } = summarize_shifts_from_file(&path, &config).unwrap();
+
+        // local times are in the shift's time zone when it has one, and Central time otherwise
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].regular_hours, 16.5);
+        assert_eq!(
+            shift_details
+                .iter()
+                .map(|detail| detail.start_time.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .collect::<Vec<_>>(),
+            ["2024-07-03T11:00:00Z", "2024-07-04T13:00:00Z"]
+        );
+
+        // shift 3 ends at 1:30 am on the night the clocks go back
+        assert_eq!(validation_report.errors.len(), 1);
+        assert!(matches!(
+            &validation_report.errors[0],
+            ShiftError::AmbiguousTimestamp {
+                record: 3,
+                shift_id: 3,
+                field: "EndTime",
+                ..
+            }
+        ));
+
+        let hours_with_policy = |ambiguous_times| {
+            let config = SummaryConfig {
+                ambiguous_times,
+                ..config.clone()
+            };
+            let summaries = summarize_shifts_from_file(&path, &config)
+                .unwrap()
+                .summaries;
+            summaries
+                .iter()
+                .find(|summary| summary.employee_id == 2)
+                .unwrap()
+                .regular_hours
+        };
+        assert_eq!(hours_with_policy(AmbiguousTimePolicy::Earliest), 1.);
+        assert_eq!(hours_with_policy(AmbiguousTimePolicy::Latest), 2.);
+    }
+
+    #[test]
+    fn test_read_compressed_employee_shifts() {
+        let json_path = PathBuf::from_str("./test_datasets/test_dataset_multiple.json").unwrap();
+        let (json_shifts, _) = read_shifts(
+            &json_path,
+            Some(InputFormat::Json),
+            &SummaryConfig::default(),
+        )
+        .unwrap();
+
+        // the gzipped JSON file doesn't have a .gz extension, so it's only detected as compressed
+        // from its contents
+        for path in [
+            "./test_datasets/test_dataset_multiple.csv.gz",
+            "./test_datasets/test_dataset_multiple.ndjson.zst",
+            "./test_datasets/test_dataset_multiple_gzipped.json",
+        ] {
+            let path = PathBuf::from_str(path).unwrap();
+            let (shifts, _) = read_shifts(&path, None, &SummaryConfig::default()).unwrap();
+
+            assert_eq!(shifts.len(), 3);
+            for (shift, json_shift) in shifts.iter().zip(&json_shifts) {
+                assert!(shift.has_same_data(json_shift));
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_input_format_of_stdin() {
+        let detect = |input: &str| InputFormat::detect(&mut input.as_bytes());
+        assert_eq!(detect("[{\"ShiftID\": 1}]").unwrap(), InputFormat::Json);
+        assert_eq!(
+            detect("\n  {\"ShiftID\": 1}\n{\"ShiftID\": 2}").unwrap(),
+            InputFormat::Ndjson
+        );
+        // CSV can't be told apart from other text, so it has to be given a format
+        assert!(detect("ShiftID,EmployeeID,StartTime,EndTime\n").is_err());
+        assert!(detect("").is_err());
+    }
+
+    #[test]
+    fn test_invalid_ndjson_lines_are_reported_by_line_number() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_invalid_records.ndjson").unwrap();
+        let config = SummaryConfig {
+            validation_mode: ValidationMode::Report,
+            ..Default::default()
+        };
+        let ShiftSummaries {
+            summaries,
+            validation_report,
+            ..
+        } = summarize_shifts_from_file(&path, &config).unwrap();
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].regular_hours, 12.5);
+
+        // the blank line after the first shift still counts as a line
+        assert_eq!(validation_report.errors.len(), 3);
+        assert!(matches!(
+            validation_report.errors[0],
+            ShiftError::BadTimestamp {
+                record: 3,
+                shift_id: 2,
+                ..
+            }
+        ));
+        assert!(matches!(
+            validation_report.errors[1],
+            ShiftError::Parse { record: 4, .. }
+        ));
+        assert!(matches!(
+            validation_report.errors[2],
+            ShiftError::Parse { record: 5, .. }
+        ));
+    }
+
+    #[test]
+    fn test_ndjson_line_that_isnt_utf8_is_a_bad_record() {
+        let mut ndjson = br#"{"ShiftID": 1, "EmployeeID": 2, "StartTime": "2024-07-03T13:00:00Z", "EndTime": "2024-07-03T21:00:00Z"}"#.to_vec();
+        ndjson.extend_from_slice(b"\n{\"ShiftID\": \xff}\n");
+        ndjson.extend_from_slice(br#"{"ShiftID": 3, "EmployeeID": 2, "StartTime": "2024-07-04T13:00:00Z", "EndTime": "2024-07-04T21:00:00Z"}"#);
+
+        let records =
+            read_ndjson_records(&ndjson[..], Arc::from("test.ndjson"), FieldNames::default())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records[0].1.is_ok());
+        assert!(matches!(
+            records[1].1,
+            Err(ShiftError::Parse { record: 2, .. })
+        ));
+        assert!(records[2].1.is_ok());
+    }
+
+    #[test]
+    fn test_invalid_record_stops_summarizing_in_strict_mode() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_invalid_records.json").unwrap();
+        let err = summarize_shifts_from_file(&path, &SummaryConfig::default()).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<ShiftError>(),
+            Some(ShiftError::BadTimestamp {
+                record: 2,
+                shift_id: 2,
+                field: "StartTime",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_records_are_reported_in_report_mode() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_invalid_records.json").unwrap();
+        let config = SummaryConfig {
+            validation_mode: ValidationMode::Report,
+            ..Default::default()
+        };
+        let ShiftSummaries {
+            summaries,
+            validation_report,
+            ..
+        } = summarize_shifts_from_file(&path, &config).unwrap();
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].regular_hours, 12.5);
+
+        assert_eq!(validation_report.errors.len(), 3);
+        assert!(matches!(
+            validation_report.errors[0],
+            ShiftError::BadTimestamp {
+                record: 2,
+                shift_id: 2,
+                ..
+            }
+        ));
+        assert!(matches!(
+            validation_report.errors[1],
+            ShiftError::Parse { record: 3, .. }
+        ));
+        assert!(matches!(
+            validation_report.errors[2],
+            ShiftError::BadTimeZone {
+                record: 4,
+                shift_id: 4,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_shifts_with_invalid_lengths_are_invalid_shifts() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_invalid_shift_lengths.json").unwrap();
+        let ShiftSummaries {
+            summaries,
+            validation_report,
+            ..
+        } = summarize_shifts_from_file(&path, &SummaryConfig::default()).unwrap();
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].regular_hours, 8.);
+        let mut invalid_shifts = summaries[0].invalid_shifts.clone();
+        invalid_shifts.sort_by_key(|invalid_shift| invalid_shift.shift_id);
+        assert_eq!(
+            invalid_shifts,
+            [
+                InvalidShift::new(2, InvalidShiftReason::EndBeforeStart),
+                InvalidShift::new(3, InvalidShiftReason::ZeroLength),
+                InvalidShift::new(4, InvalidShiftReason::TooLong),
+            ]
+        );
+
+        assert_eq!(validation_report.errors.len(), 3);
+        assert!(matches!(
+            validation_report.errors[0],
+            ShiftError::EndBeforeStart { shift_id: 2, .. }
+        ));
+        assert!(matches!(
+            validation_report.errors[1],
+            ShiftError::ZeroLength { shift_id: 3, .. }
+        ));
+        assert!(matches!(
+            validation_report.errors[2],
+            ShiftError::TooLong {
+                shift_id: 4,
+                hours: 30.,
+                max_hours: 24.,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_long_shifts_are_valid_without_a_maximum_shift_length() {
+        let path =
+            PathBuf::from_str("./test_datasets/test_dataset_invalid_shift_lengths.json").unwrap();
+        let config = SummaryConfig {
+            max_shift_hours: None,
+            ..Default::default()
+        };
+        let summaries = summarize_shifts_from_file(&path, &config)
+            .unwrap()
+            .summaries;
+
+        assert_eq!(summaries[0].regular_hours, 38.);
+        let mut invalid_shifts = summaries[0].invalid_shifts.clone();
+        invalid_shifts.sort_by_key(|invalid_shift| invalid_shift.shift_id);
+        assert_eq!(
+            invalid_shifts,
+            [
+                InvalidShift::new(2, InvalidShiftReason::EndBeforeStart),
+                InvalidShift::new(3, InvalidShiftReason::ZeroLength),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize_shifts_with_rounding_policies() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_rounding.json").unwrap();
+        let hours_with_rounding = |rounding: RoundingPolicy| {
+            let config = SummaryConfig {
+                rounding,
+                ..SummaryConfig::default()
+            };
+            let summaries = summarize_shifts_from_file(&path, &config)
+                .unwrap()
+                .summaries;
+            // the last shift ends right at the start of the next week once it's rounded, which
+            // shouldn't leave an empty summary for that week
+            assert_eq!(summaries.len(), 1);
+            summaries[0].regular_hours.as_f64()
+        };
+
+        assert_eq!(hours_with_rounding(RoundingPolicy::None), 30.1);
+        assert_eq!(hours_with_rounding(RoundingPolicy::Nearest(15)), 29.75);
+        assert_eq!(hours_with_rounding(RoundingPolicy::SevenMinuteRule), 29.75);
+        assert_eq!(hours_with_rounding(RoundingPolicy::EmployeeFavor(15)), 31.);
+    }
+
+    #[test]
+    fn test_unpaid_breaks_are_not_counted() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_breaks.json").unwrap();
+        let summarize = |config: &SummaryConfig| {
+            let ShiftSummaries {
+                mut summaries,
+                validation_report,
+                ..
+            } = summarize_shifts_from_file(path.as_path(), config).unwrap();
+            summaries.sort_by(|a, b| a.start_of_week.cmp(&b.start_of_week));
+
+            // the break in shift 4 runs past the end of the shift
+            assert_eq!(validation_report.errors.len(), 1);
+            assert!(matches!(
+                validation_report.errors[0],
+                ShiftError::BadBreak {
+                    shift_id: 4,
+                    break_number: 1,
+                    ..
+                }
+            ));
+            summaries
+        };
+
+        let config = SummaryConfig {
+            validation_mode: ValidationMode::Report,
+            ..SummaryConfig::default()
+        };
+        let summaries = summarize(&config);
+        assert_eq!(summaries.len(), 2);
+        // the break in shift 2 starts on Saturday and ends on Sunday, so it's split between the
+        // two weeks the shift was worked in
+        assert_eq!(summaries[0].regular_hours, 7.5 + 3.75);
+        assert_eq!(summaries[0].days[1].date, "2024-07-06");
+        assert_eq!(summaries[0].days[1].regular_hours, 3.75);
+        assert_eq!(summaries[1].regular_hours, 3.75 + 8.);
+
+        // shift 3 is the only one without a recorded break
+        let config = SummaryConfig {
+            automatic_break: Some(AutomaticBreak::default()),
+            ..config
+        };
+        let summaries = summarize(&config);
+        assert_eq!(summaries[0].regular_hours, 7.5 + 3.75);
+        assert_eq!(summaries[1].regular_hours, 3.75 + 7.5);
+    }
+
+    #[test]
+    fn test_break_violations_are_reported_in_the_week_the_shift_starts_in() {
+        // 8 PM Saturday to 4 AM Sunday, Central time, with the meal break taken after the week
+        // boundary at midnight
+        let shift = EmployeeShift {
+            breaks: vec![Break {
+                start_time: DateTime::parse_from_rfc3339("2024-07-07T07:00:00Z")
+                    .unwrap()
+                    .into(),
+                end_time: DateTime::parse_from_rfc3339("2024-07-07T07:30:00Z")
+                    .unwrap()
+                    .into(),
+            }],
+            ..shift(1, "2024-07-07T01:00:00Z", "2024-07-07T09:00:00Z")
+        };
+        let config = SummaryConfig {
+            break_rules: Some(BreakRules::default()),
+            ..SummaryConfig::default()
+        };
+        let mut summaries = summarize_shifts(vec![shift], &config).summaries;
+        summaries.sort_by(|a, b| a.start_of_week.cmp(&b.start_of_week));
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].start_of_week, "2024-06-30");
+        assert_eq!(
+            summaries[0].break_violations,
+            [BreakViolation {
+                shift_id: 1,
+                violation: BreakViolationKind::LateMealBreak,
+            }]
+        );
+        assert_eq!(summaries[1].start_of_week, "2024-07-07");
+        assert!(summaries[1].break_violations.is_empty());
+    }
+
+    #[test]
+    fn test_shifts_without_required_meal_breaks_are_break_violations() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_breaks.json").unwrap();
+        let break_violations = |automatic_break: Option<AutomaticBreak>| {
+            let config = SummaryConfig {
+                validation_mode: ValidationMode::Report,
+                automatic_break,
+                break_rules: Some(BreakRules::default()),
+                ..SummaryConfig::default()
+            };
+            let mut summaries = summarize_shifts_from_file(&path, &config)
+                .unwrap()
+                .summaries;
+            summaries.sort_by(|a, b| a.start_of_week.cmp(&b.start_of_week));
+            summaries
+                .into_iter()
+                .map(|summary| summary.break_violations)
+                .collect::<Vec<_>>()
+        };
+
+        // shift 3 is 8 hours long without a break
+        assert_eq!(
+            break_violations(None),
+            [
+                vec![],
+                vec![BreakViolation {
+                    shift_id: 3,
+                    violation: BreakViolationKind::MissingMealBreak,
+                }],
+            ]
+        );
+        // an automatic break is deducted from shift 3 but wasn't actually taken
+        assert_eq!(
+            break_violations(Some(AutomaticBreak::default())),
+            [
+                vec![],
+                vec![BreakViolation {
+                    shift_id: 3,
+                    violation: BreakViolationKind::MissingMealBreak,
+                }],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_shift_ids() {
+        let path = PathBuf::from_str("./test_datasets/test_dataset_duplicate_shifts.json").unwrap();
+        let ShiftSummaries {
+            summaries,
+            validation_report,
+            ..
+        } = summarize_shifts_from_file(&path, &SummaryConfig::default()).unwrap();
+
+        // the exact duplicate of shift 1 is only counted once and the conflicting records for
+        // shift 2 aren't counted at all
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].regular_hours, 12.);
+        assert_eq!(
+            summaries[0].invalid_shifts,
+            [
+                InvalidShift::new(2, InvalidShiftReason::DuplicateShiftID),
+                InvalidShift::new(2, InvalidShiftReason::DuplicateShiftID),
+            ]
+        );
+
+        assert_eq!(validation_report.warnings.len(), 1);
+        assert!(matches!(
+            validation_report.warnings[0],
+            ShiftError::DuplicateRecord {
+                record: 2,
+                shift_id: 1,
+                first_record: 1,
+                ..
+            }
+        ));
+        assert_eq!(validation_report.errors.len(), 1);
+        assert!(matches!(
+            validation_report.errors[0],
+            ShiftError::ConflictingDuplicate {
+                record: 4,
+                shift_id: 2,
+                first_record: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_shifts_in_a_directory_are_summarized_together() {
+        let path = PathBuf::from_str("./test_datasets/multiple_files").unwrap();
+        let ShiftSummaries {
+            summaries,
+            validation_report,
+            ..
+        } = summarize_shifts_from_files(&[path], &SummaryConfig::default()).unwrap();
+
+        let summary = |employee_id: u64| {
+            summaries
+                .iter()
+                .find(|summary| summary.employee_id == employee_id)
+                .unwrap()
+        };
+
+        // employee 1 works 36 hours at one location and 12 at the other in the same week
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summary(1).regular_hours, 40.);
+        assert_eq!(summary(1).overtime_hours, 8.);
+
+        // compressed files in the directory are read too
+        assert_eq!(summary(3).regular_hours, 8.);
+
+        // shift 11 from the CSV file overlaps shift 10 from the JSON file
+        assert_eq!(summary(2).regular_hours, 0.);
+        assert_eq!(
+            summary(2)
+                .invalid_shifts
+                .iter()
+                .map(|invalid_shift| invalid_shift.shift_id)
+                .collect::<Vec<_>>(),
+            [10, 11]
+        );
+
+        assert_eq!(validation_report.warnings.len(), 1);
+        assert_eq!(
+            validation_report.warnings[0].to_string(),
+            "./test_datasets/multiple_files/location_2.csv record 3, shift 10: exact duplicate of \
+             ./test_datasets/multiple_files/location_1.json record 4"
+        );
+    }
+
+    #[test]
+    fn test_glob_input() {
+        let pattern = PathBuf::from_str("./test_datasets/multiple_files/*.csv").unwrap();
+        let summaries = summarize_shifts_from_files(&[pattern], &SummaryConfig::default())
+            .unwrap()
+            .summaries;
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries
+                .iter()
+                .find(|summary| summary.employee_id == 1)
+                .unwrap()
+                .regular_hours,
+            12.
+        );
+
+        let pattern = PathBuf::from_str("./test_datasets/multiple_files/*.ndjson").unwrap();
+        assert!(summarize_shifts_from_files(&[pattern], &SummaryConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_expand_input_paths() {
+        let inputs = [
+            PathBuf::from_str("./test_datasets/multiple_files/location_2.csv").unwrap(),
+            PathBuf::from_str("./test_datasets/multiple_files").unwrap(),
+            PathBuf::from_str("-").unwrap(),
+        ];
+
+        // files in the directory are sorted by name, files given twice are only read once, and
+        // files that aren't shifts are left out
+        assert_eq!(
+            expand_input_paths(&inputs).unwrap(),
+            [
+                PathBuf::from_str("./test_datasets/multiple_files/location_2.csv").unwrap(),
+                PathBuf::from_str("./test_datasets/multiple_files/location_1.json").unwrap(),
+                PathBuf::from_str("./test_datasets/multiple_files/location_3.ndjson.gz").unwrap(),
+                PathBuf::from_str("-").unwrap(),
+            ]
+        );
+    }
+
     #[test]
     fn test_validate_raw_employee_shift() {
         let shifts = [RawEmployeeShift {
@@ -534,9 +2787,17 @@ mod tests {
             employee_id: 2,
             start_time: "2021-08-30T12:30:00.000000Z".to_string(),
             end_time: "2021-08-30T21:00:00.000000Z".to_string(),
+            time_zone: None,
+            breaks: vec![],
         }];
 
-        let shift = validate_shift(&shifts[0]).unwrap();
+        let shift = validate_shift(
+            &shifts[0],
+            &Arc::from("test.json"),
+            1,
+            &SummaryConfig::default(),
+        )
+        .unwrap();
         let expected_start_time: DateTime<Utc> =
             DateTime::parse_from_rfc3339("2021-08-30T12:30:00.000000Z")
                 .unwrap()
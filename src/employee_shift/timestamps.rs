@@ -0,0 +1,251 @@
+use chrono::{DateTime, Datelike, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::employee_shift::config::{AmbiguousTimePolicy, TimestampFormat};
+
+/// Formats tried, in order, for timestamps without an offset
+const LOCAL_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Why a timestamp couldn't be turned into a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampError {
+    /// The value isn't written in the expected format
+    Invalid,
+    /// The local time happens twice because the clocks go back, and the policy is to reject it
+    Ambiguous,
+    /// The local time never happens because the clocks go forward past it
+    Nonexistent,
+}
+
+/// Parses a timestamp written in `format`. Timestamps without an offset are local times in
+/// `time_zone`, and local times that happen twice when the clocks go back are resolved with
+/// `ambiguous_times`.
+///
+/// Times outside of the years 1 to 9999, the years an RFC 3339 timestamp can have, are invalid.
+/// Epoch timestamps and patterns can reach far beyond them, and working out the weeks of times that
+/// far out would overflow.
+pub fn parse_timestamp(
+    value: &str,
+    format: &TimestampFormat,
+    time_zone: Tz,
+    ambiguous_times: AmbiguousTimePolicy,
+) -> Result<DateTime<Utc>, TimestampError> {
+    let time = parse_timestamp_in_any_year(value, format, time_zone, ambiguous_times)?;
+    if (1..=9999).contains(&time.year()) {
+        Ok(time)
+    } else {
+        Err(TimestampError::Invalid)
+    }
+}
+
+fn parse_timestamp_in_any_year(
+    value: &str,
+    format: &TimestampFormat,
+    time_zone: Tz,
+    ambiguous_times: AmbiguousTimePolicy,
+) -> Result<DateTime<Utc>, TimestampError> {
+    match format {
+        TimestampFormat::Rfc3339 => DateTime::parse_from_rfc3339(value)
+            .map(|time| time.to_utc())
+            .map_err(|_| TimestampError::Invalid),
+        TimestampFormat::Local => {
+            // a timestamp that does have an offset is already a point in time
+            if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+                return Ok(time.to_utc());
+            }
+            let local_time = LOCAL_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+                .ok_or(TimestampError::Invalid)?;
+            from_local_time(local_time, time_zone, ambiguous_times)
+        }
+        TimestampFormat::EpochSeconds => match value.parse::<i64>() {
+            Ok(seconds) => DateTime::from_timestamp(seconds, 0).ok_or(TimestampError::Invalid),
+            // fractional seconds
+            Err(_) => value
+                .parse::<f64>()
+                .ok()
+                .filter(|seconds| seconds.is_finite())
+                .and_then(|seconds| DateTime::from_timestamp_micros((seconds * 1e6).round() as i64))
+                .ok_or(TimestampError::Invalid),
+        },
+        TimestampFormat::EpochMilliseconds => value
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_millis)
+            .ok_or(TimestampError::Invalid),
+        TimestampFormat::Pattern(pattern) => {
+            if let Ok(time) = DateTime::parse_from_str(value, pattern) {
+                return Ok(time.to_utc());
+            }
+            let local_time = NaiveDateTime::parse_from_str(value, pattern)
+                .map_err(|_| TimestampError::Invalid)?;
+            from_local_time(local_time, time_zone, ambiguous_times)
+        }
+    }
+}
+
+fn from_local_time(
+    local_time: NaiveDateTime,
+    time_zone: Tz,
+    ambiguous_times: AmbiguousTimePolicy,
+) -> Result<DateTime<Utc>, TimestampError> {
+    match time_zone.from_local_datetime(&local_time) {
+        LocalResult::Single(time) => Ok(time.to_utc()),
+        LocalResult::Ambiguous(earliest, latest) => match ambiguous_times {
+            AmbiguousTimePolicy::Reject => Err(TimestampError::Ambiguous),
+            AmbiguousTimePolicy::Earliest => Ok(earliest.to_utc()),
+            AmbiguousTimePolicy::Latest => Ok(latest.to_utc()),
+        },
+        LocalResult::None => Err(TimestampError::Nonexistent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::US::Central;
+
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time).unwrap().to_utc()
+    }
+
+    fn parse(value: &str, format: &TimestampFormat) -> Result<DateTime<Utc>, TimestampError> {
+        parse_timestamp(value, format, Central, AmbiguousTimePolicy::Reject)
+    }
+
+    #[test]
+    fn test_parse_rfc3339_with_any_offset() {
+        assert_eq!(
+            parse("2024-07-03T06:00:00-05:00", &TimestampFormat::Rfc3339),
+            Ok(utc("2024-07-03T11:00:00Z"))
+        );
+        assert_eq!(
+            parse("2024-07-03T16:30:00+05:30", &TimestampFormat::Rfc3339),
+            Ok(utc("2024-07-03T11:00:00Z"))
+        );
+        assert_eq!(
+            parse("2024-07-03 06:00:00", &TimestampFormat::Rfc3339),
+            Err(TimestampError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_parse_local_timestamps() {
+        for value in [
+            "2024-07-03 06:00:00",
+            "2024-07-03T06:00:00.000",
+            "2024-07-03 06:00",
+            "2024-07-03T11:00:00Z",
+        ] {
+            assert_eq!(
+                parse(value, &TimestampFormat::Local),
+                Ok(utc("2024-07-03T11:00:00Z")),
+                "{value}"
+            );
+        }
+        assert_eq!(
+            parse("07/03/2024 06:00", &TimestampFormat::Local),
+            Err(TimestampError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_local_times_around_daylight_saving_time() {
+        // 1:30 am happens twice on 2024-11-03 in Central time, first in CDT and then in CST
+        let ambiguous = "2024-11-03 01:30:00";
+        assert_eq!(
+            parse(ambiguous, &TimestampFormat::Local),
+            Err(TimestampError::Ambiguous)
+        );
+        let parse_ambiguous = |ambiguous_times| {
+            parse_timestamp(ambiguous, &TimestampFormat::Local, Central, ambiguous_times)
+        };
+        assert_eq!(
+            parse_ambiguous(AmbiguousTimePolicy::Earliest),
+            Ok(utc("2024-11-03T06:30:00Z"))
+        );
+        assert_eq!(
+            parse_ambiguous(AmbiguousTimePolicy::Latest),
+            Ok(utc("2024-11-03T07:30:00Z"))
+        );
+
+        // 2:30 am is skipped on 2024-03-10 when the clocks go forward
+        assert_eq!(
+            parse("2024-03-10 02:30:00", &TimestampFormat::Local),
+            Err(TimestampError::Nonexistent)
+        );
+    }
+
+    #[test]
+    fn test_parse_epoch_timestamps() {
+        assert_eq!(
+            parse("1720004400", &TimestampFormat::EpochSeconds),
+            Ok(utc("2024-07-03T11:00:00Z"))
+        );
+        assert_eq!(
+            parse("1720004400.5", &TimestampFormat::EpochSeconds),
+            Ok(utc("2024-07-03T11:00:00.5Z"))
+        );
+        assert_eq!(
+            parse("1720004400000", &TimestampFormat::EpochMilliseconds),
+            Ok(utc("2024-07-03T11:00:00Z"))
+        );
+        assert_eq!(
+            parse("2024-07-03T11:00:00Z", &TimestampFormat::EpochSeconds),
+            Err(TimestampError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_timestamps_outside_of_years_1_to_9999_are_invalid() {
+        assert_eq!(
+            parse("253402300799", &TimestampFormat::EpochSeconds),
+            Ok(utc("9999-12-31T23:59:59Z"))
+        );
+        assert_eq!(
+            parse("8210266876799", &TimestampFormat::EpochSeconds),
+            Err(TimestampError::Invalid)
+        );
+        assert_eq!(
+            parse("-62135596801", &TimestampFormat::EpochSeconds),
+            Err(TimestampError::Invalid)
+        );
+        assert_eq!(
+            parse("8210266876799000", &TimestampFormat::EpochMilliseconds),
+            Err(TimestampError::Invalid)
+        );
+        assert_eq!(
+            parse(
+                "+10000-01-01 00:00",
+                &TimestampFormat::Pattern("%Y-%m-%d %H:%M".to_string())
+            ),
+            Err(TimestampError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamps_with_a_pattern() {
+        let local = TimestampFormat::Pattern("%m/%d/%Y %I:%M %p".to_string());
+        assert_eq!(
+            parse("07/03/2024 06:00 AM", &local),
+            Ok(utc("2024-07-03T11:00:00Z"))
+        );
+
+        let with_offset = TimestampFormat::Pattern("%d.%m.%Y %H:%M %z".to_string());
+        assert_eq!(
+            parse("03.07.2024 13:00 +0200", &with_offset),
+            Ok(utc("2024-07-03T11:00:00Z"))
+        );
+        assert_eq!(
+            parse("2024-07-03 06:00", &local),
+            Err(TimestampError::Invalid)
+        );
+    }
+}
@@ -0,0 +1 @@
+pub mod employee_shift;
@@ -1,18 +1,459 @@
-mod employee_shift;
-use employee_shift::summarize_shifts::summarize_shifts_from_json_file;
-use std::{env, io::Write, path::PathBuf, str::FromStr};
+use chrono::{NaiveTime, Weekday};
+use chrono_tz::Tz;
+use clap::Parser;
+use lloyd_massiah_when_i_work_take_home::employee_shift::{
+    config::{
+        AmbiguousTimePolicy, AutomaticBreak, BreakRules, DailyOvertimeRules, InputFormat,
+        OvertimePolicy, RoundingPolicy, SeventhDayRule, SummaryConfig, TimestampFormat,
+        ValidationMode,
+    },
+    output::{shift_details_to_json, summaries_to_string, OutputFormat, OutputOptions},
+    summarize_shifts::{summarize_shifts_from_files, ShiftSummaries},
+};
+use std::{
+    collections::HashSet,
+    fs::OpenOptions,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
 
-use anyhow::Error;
+use anyhow::{anyhow, bail, Context, Error};
+
+/// Summarizes employee shifts into weekly regular and overtime hours
+#[derive(Parser, Debug)]
+struct Args {
+    /// JSON, NDJSON or CSV files containing the shifts to summarize, which can be gzip or zstd
+    /// compressed, directories of them, glob patterns like "exports/*.csv", or "-" to read from
+    /// stdin. Shifts from every input are summarized together
+    #[arg(required = true)]
+    file_paths: Vec<PathBuf>,
+
+    /// Format of the input, "json", "ndjson" or "csv". Detected from the file extension by
+    /// default, and from whether stdin starts with "[" or "{" when reading from stdin
+    #[arg(long)]
+    input_format: Option<InputFormat>,
+
+    /// How timestamps are written in the input: "rfc3339", "local" for timestamps without an
+    /// offset like "2024-07-03 06:00:00", "epoch-seconds", "epoch-milliseconds", or a strftime
+    /// pattern like "%m/%d/%Y %H:%M". Local times are in the shift's time zone
+    #[arg(long)]
+    timestamp_format: Option<TimestampFormat>,
+
+    /// What a local time that happens twice when the clocks go back means: "reject", "earliest"
+    /// or "latest"
+    #[arg(long)]
+    ambiguous_times: Option<AmbiguousTimePolicy>,
+
+    /// JSON config file, values given as flags take precedence over it
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// IANA time zone used for week boundaries, e.g. "America/Los_Angeles"
+    #[arg(long)]
+    time_zone: Option<Tz>,
+
+    /// JSON file mapping employee ids to the IANA time zone of the location they work at
+    #[arg(long)]
+    employee_time_zones: Option<PathBuf>,
+
+    /// Day of the week the workweek starts on, e.g. "Monday"
+    #[arg(long)]
+    week_start_day: Option<Weekday>,
+
+    /// Local time of day the workweek starts at, e.g. "06:00"
+    #[arg(long)]
+    week_start_time: Option<NaiveTime>,
+
+    /// Use California's daily and weekly overtime rules
+    #[arg(long)]
+    california_overtime: bool,
+
+    /// Hours worked in a week beyond this are overtime hours
+    #[arg(long)]
+    overtime_threshold: Option<f64>,
+
+    /// Hours worked in a week beyond this are double time hours
+    #[arg(long)]
+    double_time_threshold: Option<f64>,
+
+    /// Hours worked in a workday beyond this are overtime hours
+    #[arg(long)]
+    daily_overtime_threshold: Option<f64>,
+
+    /// Hours worked in a workday beyond this are double time hours. Needs a daily overtime
+    /// threshold from --daily-overtime-threshold or the config file
+    #[arg(long)]
+    daily_double_time_threshold: Option<f64>,
+
+    /// Pay overtime for the first 8 hours and double time after that on the seventh consecutive
+    /// workday of a workweek
+    #[arg(long)]
+    seventh_day_overtime: bool,
+
+    /// Shifts longer than this many hours are invalid shifts
+    #[arg(long)]
+    max_shift_hours: Option<f64>,
+
+    /// How punch times are rounded: "none", "seven-minute-rule", "nearest-<minutes>" or
+    /// "employee-favor-<minutes>", e.g. "nearest-15"
+    #[arg(long)]
+    rounding: Option<RoundingPolicy>,
+
+    /// Deduct an unpaid break of this many minutes from the middle of long shifts that don't have
+    /// any breaks recorded
+    #[arg(long)]
+    automatic_break_minutes: Option<u32>,
+
+    /// Shifts longer than this many hours get the automatic break
+    #[arg(long, requires = "automatic_break_minutes", default_value_t = 6.)]
+    automatic_break_after_hours: f64,
+
+    /// Report shifts that don't get a meal break before the 5th hour of work, or a second one
+    /// before the 10th hour, under BreakViolations
+    #[arg(long)]
+    check_meal_breaks: bool,
+
+    /// Report shifts starting less than this many hours after the employee's previous shift ended,
+    /// along with the premium hours owed for them
+    #[arg(long)]
+    minimum_rest_hours: Option<f64>,
+
+    /// Skip records that aren't valid shifts instead of stopping, and report them
+    #[arg(long)]
+    skip_invalid_records: bool,
+
+    /// Where to write the summaries, or "-" for stdout. Defaults to employee_summaries.json or
+    /// employee_summaries.csv in the current directory
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+
+    /// Overwrite output files that already exist
+    #[arg(long)]
+    force: bool,
+
+    /// Format to write the summaries in, "json" or "csv"
+    #[arg(long, default_value = "json")]
+    format: OutputFormat,
+
+    /// Write InvalidShifts as a plain list of shift ids, without the reason each shift is invalid
+    #[arg(long)]
+    flat_invalid_shifts: bool,
+
+    /// Number of decimal places to round hours to in the output, up to 9
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(0..=9))]
+    precision: u32,
+
+    /// Where to write the JSON report of skipped records and invalid shifts. Without it they're
+    /// printed to stderr
+    #[arg(long)]
+    validation_report: Option<PathBuf>,
+
+    /// Where to write the JSON report of every counted shift with its original and rounded times
+    #[arg(long)]
+    shift_report: Option<PathBuf>,
+}
 
 fn main() -> Result<(), Error> {
-    let args: Vec<String> = env::args().collect();
-    let file_path = args[1].as_str();
+    let args = Args::parse();
 
-    let path = PathBuf::from_str(file_path)?;
-    let summaries = summarize_shifts_from_json_file(&path)?;
+    let mut config = match &args.config {
+        Some(path) => SummaryConfig::from_file(path)?,
+        None => SummaryConfig::default(),
+    };
+    if let Some(time_zone) = args.time_zone {
+        config.time_zone = time_zone;
+    }
+    if let Some(path) = &args.employee_time_zones {
+        config.load_employee_time_zones(path)?;
+    }
+    if let Some(start_day) = args.week_start_day {
+        config.work_week.start_day = start_day;
+    }
+    if let Some(start_time) = args.week_start_time {
+        config.work_week.start_time = start_time;
+    }
+    if args.california_overtime {
+        config.overtime_policy = OvertimePolicy::california();
+    }
+    if let Some(threshold) = args.overtime_threshold {
+        config.overtime_policy.weekly_overtime_threshold = threshold;
+    }
+    if let Some(threshold) = args.double_time_threshold {
+        config.overtime_policy.weekly_double_time_threshold = Some(threshold);
+    }
+    override_daily_overtime(
+        &mut config.overtime_policy.daily,
+        args.daily_overtime_threshold,
+        args.daily_double_time_threshold,
+    )?;
+    if args.seventh_day_overtime {
+        config.overtime_policy.seventh_day = Some(SeventhDayRule::default());
+    }
 
-    let mut file = std::fs::File::create("./employee_summaries.json")?;
-    file.write_all(serde_json::to_string_pretty(&summaries)?.as_bytes())?;
+    if let Some(max_hours) = args.max_shift_hours {
+        config.max_shift_hours = Some(max_hours);
+    }
+    if let Some(rounding) = args.rounding {
+        config.rounding = rounding;
+    }
+    if let Some(minutes) = args.automatic_break_minutes {
+        config.automatic_break = Some(AutomaticBreak {
+            after_hours: args.automatic_break_after_hours,
+            minutes,
+        });
+    }
+    if args.check_meal_breaks {
+        config.break_rules = Some(BreakRules::default());
+    }
+    if let Some(minimum_rest_hours) = args.minimum_rest_hours {
+        config.minimum_rest_hours = Some(minimum_rest_hours);
+    }
+    if let Some(input_format) = args.input_format {
+        config.input_format = Some(input_format);
+    }
+    if let Some(timestamp_format) = args.timestamp_format {
+        config.timestamp_format = timestamp_format;
+    }
+    if let Some(ambiguous_times) = args.ambiguous_times {
+        config.ambiguous_times = ambiguous_times;
+    }
+    if args.skip_invalid_records {
+        config.validation_mode = ValidationMode::Report;
+    }
+    config.validate()?;
+
+    let output_path = args.output.clone().unwrap_or_else(|| {
+        PathBuf::from(format!("./employee_summaries.{}", args.format.extension()))
+    });
+    // checked before summarizing, so a run that would be refused doesn't write some outputs and
+    // not others
+    check_outputs(
+        [
+            Some(&output_path),
+            args.shift_report.as_ref(),
+            args.validation_report.as_ref(),
+        ]
+        .into_iter()
+        .flatten(),
+        args.force,
+    )?;
+
+    let ShiftSummaries {
+        summaries,
+        validation_report,
+        shift_details,
+    } = summarize_shifts_from_files(&args.file_paths, &config)?;
+
+    let output_options = OutputOptions {
+        format: args.format,
+        flat_invalid_shifts: args.flat_invalid_shifts,
+        precision: args.precision,
+    };
+    write_output(
+        &output_path,
+        &summaries_to_string(&summaries, &output_options)?,
+        args.force,
+    )?;
+
+    if let Some(path) = &args.shift_report {
+        write_output(
+            path,
+            &shift_details_to_json(&shift_details, &output_options)?,
+            args.force,
+        )?;
+    }
+
+    match &args.validation_report {
+        Some(path) => {
+            write_output(
+                path,
+                &serde_json::to_string_pretty(&validation_report)?,
+                args.force,
+            )?;
+        }
+        None => {
+            // errors are records or shifts that weren't counted, while warnings lost nothing
+            for error in &validation_report.errors {
+                eprintln!("skipped: {error}");
+            }
+            for warning in &validation_report.warnings {
+                eprintln!("warning: {warning}");
+            }
+        }
+    }
 
     Ok(())
 }
+
+/// Applies the daily overtime flags on top of the daily rules from the config file. Each flag only
+/// changes its own threshold, so a double time threshold from the config file is kept when just
+/// the overtime threshold is given.
+fn override_daily_overtime(
+    daily: &mut Option<DailyOvertimeRules>,
+    overtime_threshold: Option<f64>,
+    double_time_threshold: Option<f64>,
+) -> Result<(), Error> {
+    if let Some(overtime_threshold) = overtime_threshold {
+        match daily {
+            Some(daily) => daily.overtime_threshold = overtime_threshold,
+            None => {
+                *daily = Some(DailyOvertimeRules {
+                    overtime_threshold,
+                    double_time_threshold: None,
+                })
+            }
+        }
+    }
+    if let Some(double_time_threshold) = double_time_threshold {
+        match daily {
+            Some(daily) => daily.double_time_threshold = Some(double_time_threshold),
+            None => bail!(
+                "--daily-double-time-threshold needs a daily overtime threshold, from \
+                 --daily-overtime-threshold or the config file"
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that every output can be written before any of them are. Outputs can't share a file,
+/// even when it's written two different ways like `x.json` and `./x.json`, and files that already
+/// exist are refused unless `force` is set.
+fn check_outputs<'a>(paths: impl Iterator<Item = &'a PathBuf>, force: bool) -> Result<(), Error> {
+    let mut seen = HashSet::new();
+    for path in paths.filter(|path| path.as_path() != Path::new("-")) {
+        if !seen.insert(normalized_path(path)?) {
+            bail!(
+                "{} is given for more than one output",
+                path.to_string_lossy()
+            );
+        }
+        if !force && path.exists() {
+            return Err(already_exists(path));
+        }
+    }
+
+    Ok(())
+}
+
+/// The absolute path of an output with any links and `.` or `..` resolved. The file may not exist
+/// yet, so it's the directory it goes in that's resolved when it doesn't.
+fn normalized_path(path: &Path) -> Result<PathBuf, Error> {
+    let normalized = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(file_name)) if !path.exists() => {
+            // a bare file name's parent is empty rather than the current directory
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            parent.canonicalize().map(|parent| parent.join(file_name))
+        }
+        _ => path.canonicalize(),
+    };
+    normalized.with_context(|| format!("Failed to create file: {}", path.to_string_lossy()))
+}
+
+fn already_exists(path: &Path) -> Error {
+    anyhow!(
+        "{} already exists, pass --force to overwrite it",
+        path.to_string_lossy()
+    )
+}
+
+/// Writes to the file at `path`, or to stdout when the path is `-`. Files that already exist are
+/// only overwritten when `force` is set, so runs writing to the same place don't silently clobber
+/// each other.
+fn write_output(path: &Path, contents: &str, force: bool) -> Result<(), Error> {
+    if path == Path::new("-") {
+        let mut stdout = std::io::stdout().lock();
+        let written = stdout
+            .write_all(contents.as_bytes())
+            .and_then(|()| {
+                if contents.ends_with('\n') {
+                    Ok(())
+                } else {
+                    stdout.write_all(b"\n")
+                }
+            })
+            .and_then(|()| stdout.flush());
+        return match written {
+            // whatever is reading the output, like head, stopped early
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+            written => Ok(written?),
+        };
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if force {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    let mut file = options.open(path).map_err(|err| {
+        let error = if err.kind() == ErrorKind::AlreadyExists {
+            already_exists(path)
+        } else {
+            Error::from(err)
+        };
+        error.context(format!("Failed to create file: {}", path.to_string_lossy()))
+    })?;
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_overtime_flags_only_change_their_own_threshold() {
+        let from_config = Some(DailyOvertimeRules {
+            overtime_threshold: 8.,
+            double_time_threshold: Some(12.),
+        });
+
+        let mut daily = from_config;
+        override_daily_overtime(&mut daily, Some(9.), None).unwrap();
+        assert_eq!(
+            daily,
+            Some(DailyOvertimeRules {
+                overtime_threshold: 9.,
+                double_time_threshold: Some(12.),
+            })
+        );
+
+        let mut daily = from_config;
+        override_daily_overtime(&mut daily, None, Some(10.)).unwrap();
+        assert_eq!(
+            daily,
+            Some(DailyOvertimeRules {
+                overtime_threshold: 8.,
+                double_time_threshold: Some(10.),
+            })
+        );
+
+        // without daily rules in the config file the flags make new ones
+        let mut daily = None;
+        override_daily_overtime(&mut daily, Some(8.), Some(12.)).unwrap();
+        assert_eq!(daily, from_config);
+        let mut daily = None;
+        assert!(override_daily_overtime(&mut daily, None, Some(12.)).is_err());
+    }
+
+    #[test]
+    fn test_outputs_cant_share_a_file_written_two_ways() {
+        let check = |paths: &[&str]| {
+            let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+            check_outputs(paths.iter(), false)
+        };
+        assert!(check(&["x.json", "y.json", "-", "-"]).is_ok());
+        assert!(check(&["x.json", "./x.json"]).is_err());
+        assert!(check(&["x.json", "src/../x.json"]).is_err());
+        // the directory a file goes in has to exist
+        assert!(check(&["missing_directory/x.json"]).is_err());
+    }
+}